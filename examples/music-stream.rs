@@ -6,8 +6,8 @@ use std::fs::File;
 use std::io::Write;
 
 fn main() {
-    let mut file = File::open("resources/orchestral.ogg").unwrap();
-    let mut music = Music::from_stream(&mut file).unwrap();
+    let file = File::open("resources/orchestral.ogg").unwrap();
+    let mut music = Music::from_reader(file).unwrap();
 
     // Display Music informations
     println!("orchestral.ogg :");
@@ -59,7 +59,9 @@ fn main() {
         reader.read_line(&mut filename).unwrap();
 
         // Save the buffer
-        buffer.save_to_file(filename.trim());
+        if let Err(e) = buffer.save_to_file(filename.trim()) {
+            println!("Failed to save the captured sound: {}", e);
+        }
     } else {
         let mut sound = Sound::with_buffer(buffer);
 
@@ -0,0 +1,528 @@
+//! Loading resources from directories and archives by logical path.
+//!
+//! An `AssetSource` maps logical paths such as `"textures/player.png"` to data.
+//! Every resource type has a `from_asset` constructor that loads from any source,
+//! so the same loading code works for loose files during development and for a
+//! single pack file when shipping.
+//!
+//! Logical paths always use `/` as separator. Empty and `.` components are ignored,
+//! while `..` components and Windows-style prefixes are rejected, so a source can never
+//! hand out files outside of its root.
+//!
+//! # Usage example
+//!
+//! ```no_run
+//! use sfml::assets::{AssetSource, Directory, TarArchive};
+//! use sfml::audio::Music;
+//! use sfml::graphics::{Font, Texture};
+//!
+//! fn load<S: AssetSource>(assets: &S) -> sfml::error::Result<()> {
+//!     let texture = Texture::from_asset(assets, "textures/player.png")?;
+//!     let font = Font::from_asset(assets, "fonts/sansation.ttf")?;
+//!     let mut music = Music::from_asset(assets, "music/orchestral.ogg")?;
+//!     music.play();
+//!     Ok(())
+//! }
+//!
+//! // Loose files during development..
+//! load(&Directory::new("resources")).unwrap();
+//! // ..and a single archive when shipping
+//! load(&TarArchive::open("resources.tar").unwrap()).unwrap();
+//! ```
+
+use error::{self, Error, ErrorKind};
+use std::cmp;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A readable, seekable handle to the data of an asset.
+///
+/// The handle can be sent to other threads, which allows streaming a `Music` from it.
+pub trait AssetReader: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> AssetReader for T {}
+
+/// A collection of assets addressed by logical path.
+///
+/// Implement this trait to load resources from your own containers (encrypted packs,
+/// network caches..). Only `open` is required.
+pub trait AssetSource {
+    /// Open the asset at `path` for reading.
+    ///
+    /// Return a handle to the asset data, or a `FileNotFound` error if there's no such asset
+    fn open(&self, path: &str) -> error::Result<Box<dyn AssetReader>>;
+
+    /// Read the whole asset at `path` into memory.
+    fn read(&self, path: &str) -> error::Result<Vec<u8>> {
+        let mut reader = self.open(path)?;
+        let mut data = Vec::new();
+        match reader.read_to_end(&mut data) {
+            Ok(_) => Ok(data),
+            Err(e) => Err(Error::from(e).with_path(path)),
+        }
+    }
+
+    /// Read the asset at `path` as UTF-8 text.
+    fn read_to_string(&self, path: &str) -> error::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|_| {
+            Error::new(ErrorKind::DecodeFailed)
+                .with_path(path)
+                .with_message("asset is not valid UTF-8")
+        })
+    }
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for &S {
+    fn open(&self, path: &str) -> error::Result<Box<dyn AssetReader>> {
+        (**self).open(path)
+    }
+    fn read(&self, path: &str) -> error::Result<Vec<u8>> {
+        (**self).read(path)
+    }
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for Box<S> {
+    fn open(&self, path: &str) -> error::Result<Box<dyn AssetReader>> {
+        (**self).open(path)
+    }
+    fn read(&self, path: &str) -> error::Result<Vec<u8>> {
+        (**self).read(path)
+    }
+}
+
+/// Split a logical path into its components, rejecting paths that escape the root.
+fn components(path: &str) -> error::Result<Vec<&str>> {
+    let mut parts = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return Err(invalid_path(path)),
+            _ if part.contains('\\') || part.contains(':') => return Err(invalid_path(path)),
+            _ => parts.push(part),
+        }
+    }
+    if parts.is_empty() {
+        Err(invalid_path(path))
+    } else {
+        Ok(parts)
+    }
+}
+
+fn invalid_path(path: &str) -> Error {
+    Error::new(ErrorKind::FileNotFound)
+        .with_path(path)
+        .with_message("invalid asset path")
+}
+
+fn not_found(path: &str) -> Error {
+    Error::new(ErrorKind::FileNotFound).with_path(path)
+}
+
+/// Assets stored as regular files below a root directory.
+#[derive(Debug, Clone)]
+pub struct Directory {
+    root: PathBuf,
+}
+
+impl Directory {
+    /// Create a source serving the files below `root`.
+    ///
+    /// The directory isn't accessed until an asset is opened.
+    pub fn new<P: Into<PathBuf>>(root: P) -> Directory {
+        Directory { root: root.into() }
+    }
+
+    /// Return the root directory of this source.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn resolve(&self, path: &str) -> error::Result<PathBuf> {
+        let mut full = self.root.clone();
+        for part in components(path)? {
+            full.push(part);
+        }
+        Ok(full)
+    }
+}
+
+impl AssetSource for Directory {
+    fn open(&self, path: &str) -> error::Result<Box<dyn AssetReader>> {
+        match File::open(self.resolve(path)?) {
+            Ok(file) => Ok(Box::new(file)),
+            Err(e) => Err(Error::from(e).with_path(path)),
+        }
+    }
+
+    fn read(&self, path: &str) -> error::Result<Vec<u8>> {
+        fs::read(self.resolve(path)?).map_err(|e| Error::from(e).with_path(path))
+    }
+}
+
+/// The location of a file inside an archive.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    offset: u64,
+    size: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Shared(Arc<Vec<u8>>);
+
+impl AsRef<[u8]> for Shared {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[derive(Debug)]
+enum Storage {
+    File(PathBuf),
+    Memory(Shared),
+}
+
+/// Assets packed in a single tar archive.
+///
+/// Both plain ustar archives and the GNU and pax extensions for long names are supported,
+/// which covers the archives produced by the `tar` command line tool.
+/// Compressed archives (`.tar.gz`..) must be decompressed first.
+///
+/// Only the index of the archive is kept in memory. Opening an asset of an archive loaded
+/// with `TarArchive::open` reopens the archive file, so assets can be read concurrently.
+#[derive(Debug)]
+pub struct TarArchive {
+    storage: Storage,
+    entries: HashMap<String, Entry>,
+}
+
+impl TarArchive {
+    /// Index the tar archive at `path`.
+    ///
+    /// Return the archive, or an error if the file can't be read or isn't a tar archive
+    pub fn open<P: AsRef<Path>>(path: P) -> error::Result<TarArchive> {
+        let path = path.as_ref();
+        let with_path = |e: Error| e.with_path(path.to_string_lossy().into_owned());
+        let mut file = File::open(path).map_err(|e| with_path(Error::from(e)))?;
+        let entries = index(&mut file).map_err(&with_path)?;
+        Ok(TarArchive {
+               storage: Storage::File(path.to_owned()),
+               entries,
+           })
+    }
+
+    /// Index a tar archive held in memory.
+    ///
+    /// Return the archive, or an error if the data isn't a tar archive
+    pub fn from_bytes(data: Vec<u8>) -> error::Result<TarArchive> {
+        let data = Shared(Arc::new(data));
+        let entries = index(&mut Cursor::new(data.clone()))?;
+        Ok(TarArchive {
+               storage: Storage::Memory(data),
+               entries,
+           })
+    }
+
+    /// Tell whether the archive contains a file at `path`.
+    pub fn contains(&self, path: &str) -> bool {
+        self.entry(path).is_ok()
+    }
+
+    /// Return the logical paths of all the files in the archive, sorted.
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = self.entries.keys().map(|p| &p[..]).collect();
+        paths.sort();
+        paths
+    }
+
+    fn entry(&self, path: &str) -> error::Result<Entry> {
+        let key = components(path)?.join("/");
+        self.entries.get(&key).cloned().ok_or_else(|| not_found(path))
+    }
+}
+
+impl AssetSource for TarArchive {
+    fn open(&self, path: &str) -> error::Result<Box<dyn AssetReader>> {
+        let entry = self.entry(path)?;
+        match self.storage {
+            Storage::File(ref archive) => {
+                let file = File::open(archive).map_err(|e| Error::from(e).with_path(path))?;
+                Ok(Box::new(Slice::new(file, entry)))
+            }
+            Storage::Memory(ref data) => Ok(Box::new(Slice::new(Cursor::new(data.clone()), entry))),
+        }
+    }
+
+    fn read(&self, path: &str) -> error::Result<Vec<u8>> {
+        let entry = self.entry(path)?;
+        match self.storage {
+            Storage::Memory(ref data) => {
+                let start = entry.offset as usize;
+                match data.0.get(start..start + entry.size as usize) {
+                    Some(bytes) => Ok(bytes.to_vec()),
+                    None => Err(not_a_tar("truncated entry").with_path(path)),
+                }
+            }
+            Storage::File(_) => {
+                let mut data = Vec::with_capacity(entry.size as usize);
+                let mut reader = self.open(path)?;
+                match reader.read_to_end(&mut data) {
+                    Ok(_) => Ok(data),
+                    Err(e) => Err(Error::from(e).with_path(path)),
+                }
+            }
+        }
+    }
+}
+
+/// A window over the bytes of a single archive entry.
+struct Slice<R> {
+    inner: R,
+    entry: Entry,
+    pos: u64,
+    /// Whether `inner` is positioned at `entry.offset + pos`.
+    synced: bool,
+}
+
+impl<R> Slice<R> {
+    fn new(inner: R, entry: Entry) -> Slice<R> {
+        Slice {
+            inner,
+            entry,
+            pos: 0,
+            synced: false,
+        }
+    }
+}
+
+impl<R: Read + Seek> Read for Slice<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.entry.size.saturating_sub(self.pos);
+        if remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        if !self.synced {
+            let _ = self.inner.seek(SeekFrom::Start(self.entry.offset + self.pos))?;
+            self.synced = true;
+        }
+        let len = cmp::min(buf.len() as u64, remaining) as usize;
+        let read = self.inner.read(&mut buf[..len])?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Read + Seek> Seek for Slice<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => offset_by(self.entry.size, offset),
+            SeekFrom::Current(offset) => offset_by(self.pos, offset),
+        };
+        match target {
+            Some(target) => {
+                if target != self.pos {
+                    self.pos = target;
+                    self.synced = false;
+                }
+                Ok(target)
+            }
+            None => {
+                Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                   "invalid seek to a negative position"))
+            }
+        }
+    }
+}
+
+fn offset_by(base: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        base.checked_add(offset as u64)
+    } else {
+        base.checked_sub(offset.unsigned_abs())
+    }
+}
+
+const BLOCK: u64 = 512;
+
+fn not_a_tar(message: &str) -> Error {
+    Error::new(ErrorKind::UnsupportedFormat).with_message(format!("not a tar archive: {}", message))
+}
+
+/// The string stored in a NUL-padded header field.
+fn field(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Parse a numeric header field, either octal text or GNU base-256.
+fn number(bytes: &[u8]) -> Option<u64> {
+    if bytes[0] & 0x80 != 0 {
+        return bytes[1..]
+                   .iter()
+                   .try_fold(u64::from(bytes[0] & 0x7f),
+                             |n, &b| n.checked_mul(256).map(|n| n | u64::from(b)));
+    }
+    let text = field(bytes);
+    let text = text.trim_matches(|c| c == ' ' || c == '\0');
+    if text.is_empty() {
+        Some(0)
+    } else {
+        u64::from_str_radix(text, 8).ok()
+    }
+}
+
+/// Extract the `path` record of a pax extended header.
+fn pax_path(data: &[u8]) -> Option<String> {
+    let mut rest = data;
+    while !rest.is_empty() {
+        let space = rest.iter().position(|&b| b == b' ')?;
+        let len: usize = ::std::str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        if len <= space || len > rest.len() {
+            return None;
+        }
+        let record = &rest[space + 1..len];
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+        if let Some(value) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(value).into_owned());
+        }
+        rest = &rest[len..];
+    }
+    None
+}
+
+fn read_data<R: Read>(reader: &mut R, size: u64) -> error::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let _ = reader.take(size).read_to_end(&mut data)?;
+    if (data.len() as u64) < size {
+        return Err(not_a_tar("truncated entry"));
+    }
+    Ok(data)
+}
+
+/// Build the path → entry index of a tar archive.
+fn index<R: Read + Seek>(reader: &mut R) -> error::Result<HashMap<String, Entry>> {
+    let mut entries = HashMap::new();
+    let mut offset = 0;
+    let mut long_name = None;
+    let mut header = [0u8; BLOCK as usize];
+    let len = reader.seek(SeekFrom::End(0))?;
+    loop {
+        let _ = reader.seek(SeekFrom::Start(offset))?;
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            // Some writers omit the two terminating zero blocks
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof && offset > 0 => break,
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(not_a_tar("missing header"))
+            }
+            Err(e) => return Err(e.into()),
+        }
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let checksum = number(&header[148..156]).ok_or_else(|| not_a_tar("bad checksum"))?;
+        let sum = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { u64::from(b' ') } else { u64::from(b) })
+            .sum::<u64>();
+        if sum != checksum {
+            return Err(not_a_tar("bad checksum"));
+        }
+        let size = number(&header[124..136]).ok_or_else(|| not_a_tar("bad entry size"))?;
+        let data = offset + BLOCK;
+        if data.checked_add(size).is_none_or(|end| end > len) {
+            return Err(not_a_tar("truncated entry"));
+        }
+        match header[156] {
+            b'L' => long_name = Some(field(&read_data(reader, size)?)),
+            b'x' => long_name = pax_path(&read_data(reader, size)?),
+            b'0' | 0 | b'7' => {
+                let name = match long_name.take() {
+                    Some(name) => name,
+                    None => {
+                        let name = field(&header[0..100]);
+                        let prefix = field(&header[345..500]);
+                        if &header[257..262] == b"ustar" && !prefix.is_empty() {
+                            format!("{}/{}", prefix, name)
+                        } else {
+                            name
+                        }
+                    }
+                };
+                // Entries that can't be addressed by a logical path are skipped
+                if let Ok(parts) = components(&name) {
+                    let _ = entries.insert(parts.join("/"),
+                                           Entry {
+                                               offset: data,
+                                               size,
+                                           });
+                }
+            }
+            // Directories, links and other special entries carry no asset data
+            _ => long_name = None,
+        }
+        offset = data + size.div_ceil(BLOCK) * BLOCK;
+    }
+    Ok(entries)
+}
+
+#[test]
+fn tar_archive_entries() {
+    fn header(name: &str, kind: u8, size: usize) -> Vec<u8> {
+        let mut block = vec![0u8; BLOCK as usize];
+        block[..name.len()].copy_from_slice(name.as_bytes());
+        block[124..135].copy_from_slice(format!("{:011o}", size).as_bytes());
+        block[156] = kind;
+        block[257..263].copy_from_slice(b"ustar\0");
+        for b in &mut block[148..156] {
+            *b = b' ';
+        }
+        let sum: u32 = block.iter().map(|&b| u32::from(b)).sum();
+        block[148..155].copy_from_slice(format!("{:06o}\0", sum).as_bytes());
+        block
+    }
+    fn padded(data: &[u8]) -> Vec<u8> {
+        let mut data = data.to_vec();
+        let len = data.len().div_ceil(512) * 512;
+        data.resize(len, 0);
+        data
+    }
+
+    let long = format!("{}/deep.txt", "nested".repeat(20));
+    let mut tar = Vec::new();
+    tar.extend(header("./textures/", b'5', 0));
+    tar.extend(header("./textures/a.txt", b'0', 5));
+    tar.extend(padded(b"hello"));
+    tar.extend(header("././@LongLink", b'L', long.len() + 1));
+    tar.extend(padded(format!("{}\0", long).as_bytes()));
+    tar.extend(header("truncated", b'0', 4));
+    tar.extend(padded(b"deep"));
+    tar.extend(vec![0u8; 1024]);
+
+    let archive = TarArchive::from_bytes(tar).unwrap();
+    assert_eq!(archive.paths(), vec![&long[..], "textures/a.txt"]);
+    assert_eq!(archive.read("textures//./a.txt").unwrap(), b"hello");
+    assert_eq!(archive.read(&long).unwrap(), b"deep");
+    assert_eq!(archive.read("textures/b.txt").unwrap_err().kind(),
+               ErrorKind::FileNotFound);
+    assert_eq!(archive.read("../a.txt").unwrap_err().kind(),
+               ErrorKind::FileNotFound);
+
+    let mut reader = archive.open("textures/a.txt").unwrap();
+    let mut buf = String::new();
+    assert_eq!(reader.seek(SeekFrom::End(-3)).unwrap(), 2);
+    let _ = reader.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "llo");
+
+    assert_eq!(TarArchive::from_bytes(vec![1; 1024]).unwrap_err().kind(),
+               ErrorKind::UnsupportedFormat);
+    // An entry whose data runs past the end of the archive
+    let mut truncated = header("big.txt", b'0', 4096);
+    truncated.extend(padded(b"short"));
+    assert_eq!(TarArchive::from_bytes(truncated).unwrap_err().kind(),
+               ErrorKind::UnsupportedFormat);
+}
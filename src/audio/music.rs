@@ -1,12 +1,16 @@
+use assets::AssetSource;
 use audio::{SoundSource, SoundStatus};
 use audio::csfml_audio_sys as ffi;
 use csfml_system_sys::sfBool;
+use error::{self, AUDIO_FORMATS, Error, ErrorKind};
 use inputstream::InputStream;
 use sf_bool_ext::SfBoolExt;
 use std::ffi::CString;
 use std::io::{Read, Seek};
+use std::marker::PhantomData;
 use std::mem;
 use system::Time;
+use system::err;
 use system::Vector3f;
 use system::raw_conv::{FromRaw, Raw};
 
@@ -18,6 +22,8 @@ use system::raw_conv::{FromRaw, Raw};
 /// uncompressed: by streaming it instead of loading it entirely, you avoid saturating the memory
 /// and have almost no loading delay. This implies that the underlying resource
 /// (file, stream or memory buffer) must remain valid for the lifetime of the `Music` object.
+/// The `'src` lifetime ties a `Music` to the stream or memory buffer it borrows;
+/// musics opened from a file or from an owned reader (see `Music::from_reader`) are `'static`.
 ///
 /// Apart from that, a `Music` has almost the same features as the
 /// `SoundBuffer` / `Sound` pair: you can play/pause/stop it, request its parameters
@@ -45,11 +51,14 @@ use system::raw_conv::{FromRaw, Raw};
 /// music.play();
 /// ```
 #[derive(Debug)]
-pub struct Music {
+pub struct Music<'src> {
     music: *mut ffi::sfMusic,
+    /// The stream the music is played from, SFML keeps reading from it during playback.
+    _stream: Option<InputStream<'src>>,
+    _source: PhantomData<&'src [u8]>,
 }
 
-impl Music {
+impl Music<'static> {
     /// Create a new music and load it from a file
     ///
     /// This function doesn't start playing the music (call
@@ -61,37 +70,109 @@ impl Music {
     /// # Arguments
     /// * filename - Path of the music file to open
     ///
-    /// Return Some(Music) or None
-    pub fn from_file(filename: &str) -> Option<Music> {
+    /// Return the opened Music, or an error describing why the file couldn't be opened
+    pub fn from_file(filename: &str) -> error::Result<Music<'static>> {
+        error::check_readable(filename)?;
         let c_str = CString::new(filename.as_bytes()).unwrap();
-        let music_tmp: *mut ffi::sfMusic = unsafe { ffi::sfMusic_createFromFile(c_str.as_ptr()) };
+        let (music_tmp, message) = err::capture("Music::from_file", || unsafe {
+            ffi::sfMusic_createFromFile(c_str.as_ptr())
+        });
         if music_tmp.is_null() {
-            None
+            Err(error::load_failure(filename, AUDIO_FORMATS).with_message(message))
         } else {
-            Some(Music { music: music_tmp })
+            Ok(Music {
+                   music: music_tmp,
+                   _stream: None,
+                   _source: PhantomData,
+               })
         }
     }
 
+    /// Create a new music that takes ownership of a reader (a struct implementing Read and Seek)
+    ///
+    /// The reader is moved into the `Music` and dropped along with it, so the music can be
+    /// stored or returned freely, e.g. when streaming from an archive or a network buffer.
+    /// It is read from the audio thread during playback, hence the `Send` bound.
+    /// Errors and panics raised by the reader during playback end the playback.
+    ///
+    /// This function doesn't start playing the music (call
+    /// `Music::play` to do so).
+    /// See `Music::from_file` for the list of supported audio formats.
+    ///
+    /// # Arguments
+    /// * reader - Your struct, implementing Read and Seek
+    ///
+    /// Return the opened Music, or an error if the reader failed or the data couldn't be decoded
+    ///
+    /// # Usage example
+    ///
+    /// ```no_run
+    /// use sfml::audio::Music;
+    /// use std::io::Cursor;
+    ///
+    /// fn load_music(data: Vec<u8>) -> Music<'static> {
+    ///     Music::from_reader(Cursor::new(data)).unwrap()
+    /// }
+    /// ```
+    pub fn from_reader<R>(reader: R) -> error::Result<Music<'static>>
+        where R: Read + Seek + Send + 'static
+    {
+        Music::from_input_stream("Music::from_reader", InputStream::new(reader))
+    }
+
+    /// Create a new music streamed from an asset
+    ///
+    /// The asset is opened through `AssetSource::open` and streamed during playback,
+    /// see `Music::from_reader`.
+    ///
+    /// # Arguments
+    /// * source - The directory or archive containing the asset
+    /// * path - Logical path of the music file in `source`
+    ///
+    /// Return the opened Music, or an error describing why the asset couldn't be opened
+    pub fn from_asset<S: AssetSource + ?Sized>(source: &S,
+                                               path: &str)
+                                               -> error::Result<Music<'static>> {
+        let reader = source.open(path)?;
+        Music::from_reader(reader).map_err(|e| e.with_path(path))
+    }
+}
+
+impl<'src> Music<'src> {
     /// Create a new music and load it from a stream (a struct implementing Read and Seek)
     ///
     /// This function doesn't start playing the music (call
     /// `Music::play` to do so).
-    /// Here is a complete list of all the supported audio formats:
-    /// ogg, wav, flac, aiff, au, raw, paf, svx, nist, voc, ircam,
-    /// w64, mat4, mat5 pvf, htk, sds, avr, sd2, caf, wve, mpc2k, rf64.
+    /// See `Music::from_file` for the list of supported audio formats.
+    ///
+    /// The stream is read from the audio thread during playback, so it stays borrowed
+    /// for as long as the music is alive. Use `Music::from_reader` to hand over the stream instead.
+    /// Errors and panics raised by the stream during playback end the playback.
     ///
     /// # Arguments
     /// * stream - Your struct, implementing Read and Seek
     ///
-    /// Return Some(Music) or None
-    pub fn from_stream<T: Read + Seek>(stream: &mut T) -> Option<Music> {
-        let mut input_stream = InputStream::new(stream);
-        let music_tmp: *mut ffi::sfMusic =
-            unsafe { ffi::sfMusic_createFromStream(&mut input_stream.0) };
+    /// Return the opened Music, or an error if the stream failed or the data couldn't be decoded
+    pub fn from_stream<T>(stream: &'src mut T) -> error::Result<Music<'src>>
+        where T: Read + Seek + Send
+    {
+        Music::from_input_stream("Music::from_stream", InputStream::new(stream))
+    }
+
+    fn from_input_stream(origin: &'static str,
+                         mut input_stream: InputStream<'src>)
+                         -> error::Result<Music<'src>> {
+        let (music_tmp, message) =
+            err::capture(origin,
+                         || unsafe { ffi::sfMusic_createFromStream(input_stream.raw_mut()) });
         if music_tmp.is_null() {
-            None
+            Err(input_stream.load_failure(message))
         } else {
-            Some(Music { music: music_tmp })
+            Ok(Music {
+                   music: music_tmp,
+                   _stream: Some(input_stream),
+                   _source: PhantomData,
+               })
         }
     }
 
@@ -103,17 +184,24 @@ impl Music {
     /// ogg, wav, flac, aiff, au, raw, paf, svx, nist, voc, ircam,
     /// w64, mat4, mat5 pvf, htk, sds, avr, sd2, caf, wve, mpc2k, rf64.
     ///
+    /// The data is read during playback, so it stays borrowed for as long as the music is alive.
+    ///
     /// # Arguments
     /// * mem - Pointer to the file data in memory
     ///
-    /// Return Some(Music) or None
-    pub fn from_memory(mem: &[u8]) -> Option<Music> {
-        let music_tmp =
-            unsafe { ffi::sfMusic_createFromMemory(mem.as_ptr() as *const _, mem.len()) };
+    /// Return the opened Music, or an error if the data couldn't be decoded
+    pub fn from_memory(mem: &'src [u8]) -> error::Result<Music<'src>> {
+        let (music_tmp, message) = err::capture("Music::from_memory", || unsafe {
+            ffi::sfMusic_createFromMemory(mem.as_ptr() as *const _, mem.len())
+        });
         if music_tmp.is_null() {
-            None
+            Err(Error::new(ErrorKind::DecodeFailed).with_message(message))
         } else {
-            Some(Music { music: music_tmp })
+            Ok(Music {
+                   music: music_tmp,
+                   _stream: None,
+                   _source: PhantomData,
+               })
         }
     }
 
@@ -215,7 +303,7 @@ impl Music {
     }
 }
 
-impl SoundSource for Music {
+impl<'src> SoundSource for Music<'src> {
     fn set_pitch(&mut self, pitch: f32) {
         unsafe { ffi::sfMusic_setPitch(self.music, pitch) }
     }
@@ -254,7 +342,7 @@ impl SoundSource for Music {
     }
 }
 
-impl Drop for Music {
+impl<'src> Drop for Music<'src> {
     fn drop(&mut self) {
         unsafe {
             ffi::sfMusic_destroy(self.music);
@@ -1,4 +1,6 @@
+use assets::AssetSource;
 use audio::csfml_audio_sys as ffi;
+use error::{self, AUDIO_FORMATS, Error, ErrorKind};
 use inputstream::InputStream;
 use sf_bool_ext::SfBoolExt;
 use std::borrow::{Borrow, ToOwned};
@@ -6,6 +8,7 @@ use std::ffi::CString;
 use std::io::{Read, Seek};
 use std::ops::Deref;
 use system::Time;
+use system::err;
 use system::raw_conv::{FromRaw, Raw};
 
 
@@ -84,10 +87,17 @@ impl SoundBufferRef {
     /// # Arguments
     /// * filename - Path of the sound file to write
     ///
-    /// Return true if saving succeeded, false if it faileds
-    pub fn save_to_file(&self, filename: &str) -> bool {
+    /// Return an error describing the failure if the sound buffer couldn't be saved
+    pub fn save_to_file(&self, filename: &str) -> error::Result<()> {
         let c_str = CString::new(filename.as_bytes()).unwrap();
-        unsafe { ffi::sfSoundBuffer_saveToFile(self.raw(), c_str.as_ptr()) }.to_bool()
+        let (saved, message) = err::capture("SoundBuffer::save_to_file", || unsafe {
+            ffi::sfSoundBuffer_saveToFile(self.raw(), c_str.as_ptr())
+        });
+        if saved.to_bool() {
+            Ok(())
+        } else {
+            Err(error::save_failure(filename, AUDIO_FORMATS).with_message(message))
+        }
     }
 
     /// Get the number of samples stored in a sound buffer
@@ -139,51 +149,70 @@ impl SoundBuffer {
     /// # Arguments
     /// * filename - Path of the sound file to load
     ///
-    /// Return an option to a SoundBuffer object or None.
-    pub fn from_file(filename: &str) -> Option<SoundBuffer> {
+    /// Return the loaded SoundBuffer, or an error describing why the file couldn't be loaded
+    pub fn from_file(filename: &str) -> error::Result<SoundBuffer> {
+        error::check_readable(filename)?;
         let c_str = CString::new(filename.as_bytes()).unwrap();
-        let sound_buffer: *mut ffi::sfSoundBuffer =
-            unsafe { ffi::sfSoundBuffer_createFromFile(c_str.as_ptr()) };
+        let (sound_buffer, message) = err::capture("SoundBuffer::from_file", || unsafe {
+            ffi::sfSoundBuffer_createFromFile(c_str.as_ptr())
+        });
         if sound_buffer.is_null() {
-            None
+            Err(error::load_failure(filename, AUDIO_FORMATS).with_message(message))
         } else {
-            Some(SoundBuffer { sound_buffer: sound_buffer })
+            Ok(SoundBuffer { sound_buffer: sound_buffer })
         }
     }
+    /// Load the sound buffer from an asset.
+    ///
+    /// # Arguments
+    /// * source - The directory or archive containing the asset
+    /// * path - Logical path of the sound file in `source`
+    pub fn from_asset<S: AssetSource + ?Sized>(source: &S, path: &str) -> error::Result<Self> {
+        let data = source.read(path)?;
+        SoundBuffer::from_memory(&data).map_err(|e| e.with_path(path))
+    }
     /// Load the sound buffer from a file in memory.
-    pub fn from_memory(data: &[u8]) -> Option<Self> {
-        let sound_buffer =
-            unsafe { ffi::sfSoundBuffer_createFromMemory(data.as_ptr() as _, data.len()) };
+    pub fn from_memory(data: &[u8]) -> error::Result<Self> {
+        let (sound_buffer, message) = err::capture("SoundBuffer::from_memory", || unsafe {
+            ffi::sfSoundBuffer_createFromMemory(data.as_ptr() as _, data.len())
+        });
         if sound_buffer.is_null() {
-            None
+            Err(Error::new(ErrorKind::DecodeFailed).with_message(message))
         } else {
-            Some(SoundBuffer { sound_buffer: sound_buffer })
+            Ok(SoundBuffer { sound_buffer: sound_buffer })
         }
     }
     /// Load the sound buffer from a custom stream.
-    pub fn from_stream<T: Read + Seek>(stream: &mut T) -> Option<Self> {
+    pub fn from_stream<T: Read + Seek>(stream: &mut T) -> error::Result<Self> {
         let mut stream = InputStream::new(stream);
-        let buffer = unsafe { ffi::sfSoundBuffer_createFromStream(&mut stream.0) };
+        let (buffer, message) = err::capture("SoundBuffer::from_stream", || unsafe {
+            ffi::sfSoundBuffer_createFromStream(stream.raw_mut())
+        });
         if buffer.is_null() {
-            None
+            Err(stream.load_failure(message))
         } else {
-            Some(SoundBuffer { sound_buffer: buffer })
+            Ok(SoundBuffer { sound_buffer: buffer })
         }
     }
     /// Load the sound buffer from a slice of audio samples.
     ///
     /// The assumed format of the audio samples is 16 bits signed integer.
-    pub fn from_samples(samples: &[i16], channel_count: u32, sample_rate: u32) -> Option<Self> {
-        let buffer = unsafe {
+    ///
+    /// Fails with `ErrorKind::UnsupportedFormat` if the channel count or sample rate is invalid.
+    pub fn from_samples(samples: &[i16],
+                        channel_count: u32,
+                        sample_rate: u32)
+                        -> error::Result<Self> {
+        let (buffer, message) = err::capture("SoundBuffer::from_samples", || unsafe {
             ffi::sfSoundBuffer_createFromSamples(samples.as_ptr(),
                                                  samples.len() as _,
                                                  channel_count,
                                                  sample_rate)
-        };
+        });
         if buffer.is_null() {
-            None
+            Err(Error::new(ErrorKind::UnsupportedFormat).with_message(message))
         } else {
-            Some(SoundBuffer { sound_buffer: buffer })
+            Ok(SoundBuffer { sound_buffer: buffer })
         }
     }
 }
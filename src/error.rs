@@ -0,0 +1,221 @@
+//! Error type returned when loading or saving a resource fails.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::result;
+
+/// Result type used by the fallible resource constructors of this crate.
+pub type Result<T> = result::Result<T, Error>;
+
+/// The category of a resource loading or saving failure.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ErrorKind {
+    /// The file doesn't exist or couldn't be opened.
+    FileNotFound,
+    /// The format of the resource isn't supported by SFML.
+    UnsupportedFormat,
+    /// The resource data is corrupt or invalid (bad image header, shader that doesn't compile..).
+    DecodeFailed,
+    /// The resource couldn't be encoded and written out.
+    EncodeFailed,
+    /// The graphics or audio resource couldn't be allocated by the driver.
+    ResourceCreation,
+    /// Reading from or writing to a stream failed.
+    Io,
+}
+
+impl ErrorKind {
+    fn description(&self) -> &'static str {
+        match *self {
+            ErrorKind::FileNotFound => "file not found",
+            ErrorKind::UnsupportedFormat => "unsupported format",
+            ErrorKind::DecodeFailed => "failed to decode resource",
+            ErrorKind::EncodeFailed => "failed to encode resource",
+            ErrorKind::ResourceCreation => "failed to allocate resource",
+            ErrorKind::Io => "I/O error",
+        }
+    }
+}
+
+/// An error that occurred while loading or saving a resource.
+///
+/// Besides its `ErrorKind`, the error carries the path of the file involved (if any),
+/// the text SFML wrote to its error stream while the operation ran, and the underlying
+/// `io::Error` when the failure was caused by the filesystem or a user-provided stream.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    path: Option<String>,
+    message: String,
+    cause: Option<io::Error>,
+}
+
+impl Error {
+    /// Create a new error of the given kind.
+    pub fn new(kind: ErrorKind) -> Self {
+        Error {
+            kind,
+            path: None,
+            message: String::new(),
+            cause: None,
+        }
+    }
+
+    /// Attach the path of the file involved in the failure.
+    pub fn with_path<P: Into<String>>(mut self, path: P) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Attach a diagnostic message.
+    pub fn with_message<M: Into<String>>(mut self, message: M) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Returns the category of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the path of the file involved in the failure, if any.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_ref().map(|p| &p[..])
+    }
+
+    /// Returns the diagnostic text associated with this error.
+    ///
+    /// For errors reported by SFML, this is what SFML wrote to its error stream while the
+    /// failing operation ran, if an error handler was installed to capture it
+    /// (see `system::set_error_handler`).
+    /// It is empty if nothing more specific than the `ErrorKind` is known.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the underlying I/O error, if the failure was caused by one.
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.cause.as_ref()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.kind.description())?;
+        if let Some(ref path) = self.path {
+            write!(f, " ({})", path)?;
+        }
+        if let Some(ref cause) = self.cause {
+            write!(f, ": {}", cause)?;
+        }
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.cause.as_ref().map(|cause| -> &(dyn StdError + 'static) { cause })
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(src: io::Error) -> Self {
+        let kind = match src.kind() {
+            io::ErrorKind::NotFound => ErrorKind::FileNotFound,
+            _ => ErrorKind::Io,
+        };
+        Error {
+            kind,
+            path: None,
+            message: String::new(),
+            cause: Some(src),
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(src: ErrorKind) -> Self {
+        Error::new(src)
+    }
+}
+
+/// File extensions SFML can decode images from.
+#[cfg(feature="graphics")]
+pub(crate) const IMAGE_LOAD_FORMATS: &[&str] = &["bmp", "png", "tga", "jpg", "jpeg", "gif",
+                                                 "psd", "hdr", "pic"];
+/// File extensions SFML can encode images to.
+#[cfg(feature="graphics")]
+pub(crate) const IMAGE_SAVE_FORMATS: &[&str] = &["bmp", "png", "tga", "jpg", "jpeg"];
+/// File extensions SFML can read and write audio from.
+#[cfg(feature="audio")]
+pub(crate) const AUDIO_FORMATS: &[&str] = &["wav", "ogg", "oga", "flac"];
+
+/// Make sure the file at `path` can be opened for reading.
+#[cfg(any(feature="graphics", feature="audio"))]
+pub(crate) fn check_readable(path: &str) -> Result<()> {
+    match ::std::fs::File::open(path) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(Error::from(e).with_path(path)),
+    }
+}
+
+/// Build the error for a file that exists but that SFML refused to load.
+///
+/// Files whose extension isn't in `formats` are reported as `UnsupportedFormat`,
+/// everything else as `DecodeFailed`.
+/// An empty `formats` list means the format can't be deduced from the extension.
+#[cfg(any(feature="graphics", feature="audio"))]
+pub(crate) fn load_failure(path: &str, formats: &[&str]) -> Error {
+    let kind = if formats.is_empty() || has_extension(path, formats) {
+        ErrorKind::DecodeFailed
+    } else {
+        ErrorKind::UnsupportedFormat
+    };
+    Error::new(kind).with_path(path)
+}
+
+/// Build the error for a file that SFML failed to write.
+#[cfg(any(feature="graphics", feature="audio"))]
+pub(crate) fn save_failure(path: &str, formats: &[&str]) -> Error {
+    let parent_missing = match ::std::path::Path::new(path).parent() {
+        Some(parent) => !parent.as_os_str().is_empty() && !parent.is_dir(),
+        None => false,
+    };
+    let kind = if !has_extension(path, formats) {
+        ErrorKind::UnsupportedFormat
+    } else if parent_missing {
+        ErrorKind::FileNotFound
+    } else {
+        ErrorKind::EncodeFailed
+    };
+    Error::new(kind).with_path(path)
+}
+
+#[cfg(any(feature="graphics", feature="audio"))]
+fn has_extension(path: &str, formats: &[&str]) -> bool {
+    match ::std::path::Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => formats.iter().any(|f| f.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
+
+#[cfg(feature="graphics")]
+#[test]
+fn classify_failures() {
+    assert_eq!(load_failure("a.png", IMAGE_LOAD_FORMATS).kind(),
+               ErrorKind::DecodeFailed);
+    assert_eq!(load_failure("a.PNG", IMAGE_LOAD_FORMATS).kind(),
+               ErrorKind::DecodeFailed);
+    assert_eq!(load_failure("a.xyz", IMAGE_LOAD_FORMATS).kind(),
+               ErrorKind::UnsupportedFormat);
+    assert_eq!(save_failure("a.gif", IMAGE_SAVE_FORMATS).kind(),
+               ErrorKind::UnsupportedFormat);
+    assert_eq!(save_failure("/nonexistent-dir/a.png", IMAGE_SAVE_FORMATS).kind(),
+               ErrorKind::FileNotFound);
+    assert_eq!(check_readable("/nonexistent-dir/a.png").unwrap_err().kind(),
+               ErrorKind::FileNotFound);
+}
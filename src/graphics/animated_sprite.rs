@@ -0,0 +1,501 @@
+use graphics::{Drawable, IntRect, RenderStates, RenderTarget, Sprite, TextureRef, Transform,
+               Transformable};
+use system::{Time, Vector2f};
+
+/// How an `Animation` goes on after its last frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum PlaybackMode {
+    /// Start again from the first frame.
+    Loop,
+    /// Play the frames backwards down to the first one, then forwards again, and so on.
+    PingPong,
+    /// Stop on the last frame.
+    OneShot,
+}
+
+/// Something that happened while updating an `Animation`.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum AnimationEvent {
+    /// A frame with an event (see `Animation::set_frame_event`) was entered.
+    Frame {
+        /// The index of the frame.
+        index: usize,
+        /// The name of the event.
+        name: String,
+    },
+    /// A looping or ping-pong animation went back to its first frame.
+    Looped,
+    /// A one-shot animation finished, it stays on its last frame.
+    Finished,
+}
+
+/// The timing of a sprite sheet animation: a list of frames, each shown for its own duration.
+///
+/// An animation doesn't draw anything, it only tells which frame should be shown: it advances
+/// by the time deltas given to `update`, which makes it easy to test without a window.
+/// `AnimatedSprite` applies it to a `Sprite`.
+///
+/// Frames can carry a named event, reported by `update` when the frame is entered, to play a
+/// footstep sound or spawn a projectile on the right frame.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{Animation, AnimationEvent, IntRect, PlaybackMode};
+/// use sfml::system::Time;
+///
+/// let frames: Vec<IntRect> = (0..6).map(|i| IntRect::new(i * 32, 0, 32, 32)).collect();
+/// let mut walk = Animation::with_frames(&frames, Time::milliseconds(100));
+/// walk.set_frame_event(2, "step");
+/// walk.set_frame_event(5, "step");
+/// walk.set_mode(PlaybackMode::Loop);
+/// walk.play();
+///
+/// for event in walk.update(Time::milliseconds(250)) {
+///     if let AnimationEvent::Frame { name, .. } = event {
+///         println!("{}", name);
+///     }
+/// }
+/// assert_eq!(walk.current_frame(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Animation {
+    frames: Vec<(IntRect, Time)>,
+    events: Vec<Option<String>>,
+    mode: PlaybackMode,
+    speed: f32,
+    playing: bool,
+    current: usize,
+    /// Microseconds spent in the current frame.
+    elapsed: i64,
+    /// Whether a ping-pong animation is going backwards.
+    backwards: bool,
+    /// Whether the event of the current frame was reported.
+    entered: bool,
+}
+
+impl Animation {
+    /// Create an animation without frames, which loops at normal speed once started.
+    pub fn new() -> Animation {
+        Animation {
+            frames: Vec::new(),
+            events: Vec::new(),
+            mode: PlaybackMode::Loop,
+            speed: 1.,
+            playing: false,
+            current: 0,
+            elapsed: 0,
+            backwards: false,
+            entered: false,
+        }
+    }
+
+    /// Create an animation whose frames all last `duration`.
+    pub fn with_frames(rects: &[IntRect], duration: Time) -> Animation {
+        let mut animation = Animation::new();
+        for rect in rects {
+            animation.add_frame(*rect, duration);
+        }
+        animation
+    }
+
+    /// Add a frame, showing `rect` of the texture for `duration`.
+    ///
+    /// Frames with a duration of zero or less are skipped when playing.
+    pub fn add_frame(&mut self, rect: IntRect, duration: Time) {
+        self.frames.push((rect, duration));
+        self.events.push(None);
+    }
+
+    /// Return the number of frames.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Return the texture rect and the duration of a frame.
+    pub fn frame(&self, index: usize) -> Option<(IntRect, Time)> {
+        self.frames.get(index).cloned()
+    }
+
+    /// Return the total duration of the frames.
+    pub fn duration(&self) -> Time {
+        Time::microseconds(self.frames.iter().map(|f| duration_of(f.1)).sum())
+    }
+
+    /// Report an event named `name` whenever the frame at `index` is entered.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set_frame_event<N: Into<String>>(&mut self, index: usize, name: N) {
+        self.events[index] = Some(name.into());
+    }
+
+    /// Remove the event of the frame at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn clear_frame_event(&mut self, index: usize) {
+        self.events[index] = None;
+    }
+
+    /// Set what happens after the last frame.
+    pub fn set_mode(&mut self, mode: PlaybackMode) {
+        self.mode = mode;
+    }
+
+    /// Return what happens after the last frame.
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    /// Set the playback speed: 2 plays twice as fast, 0.5 twice as slow.
+    ///
+    /// Negative speeds are treated as 0.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.);
+    }
+
+    /// Return the playback speed.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Start or resume playing.
+    ///
+    /// A finished one-shot animation starts again from its first frame.
+    pub fn play(&mut self) {
+        if self.is_finished() {
+            self.set_current_frame(0);
+        }
+        self.playing = true;
+    }
+
+    /// Pause, keeping the current frame.
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Pause, and go back to the first frame.
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.set_current_frame(0);
+    }
+
+    /// Tell whether the animation is playing.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Tell whether a one-shot animation reached the end of its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.mode == PlaybackMode::OneShot && !self.frames.is_empty() &&
+        self.current == self.frames.len() - 1 &&
+        self.elapsed >= duration_of(self.frames[self.current].1)
+    }
+
+    /// Return the index of the current frame.
+    pub fn current_frame(&self) -> usize {
+        self.current
+    }
+
+    /// Return the texture rect of the current frame, `None` if there are no frames.
+    pub fn current_rect(&self) -> Option<IntRect> {
+        self.frames.get(self.current).map(|f| f.0)
+    }
+
+    /// Jump to the start of a frame, clamped to the frames.
+    ///
+    /// Its event is reported by the next `update` while playing.
+    pub fn set_current_frame(&mut self, index: usize) {
+        self.current = index.min(self.frames.len().saturating_sub(1));
+        self.elapsed = 0;
+        self.backwards = false;
+        self.entered = false;
+    }
+
+    /// Advance the animation by `delta`, scaled by the speed, and return what happened in
+    /// order.
+    ///
+    /// Does nothing if the animation is paused or has no frames to show. Whole cycles of a
+    /// looping or ping-pong animation are skipped at once: they are reported by a single
+    /// `Looped` event, without the events of their frames.
+    pub fn update(&mut self, delta: Time) -> Vec<AnimationEvent> {
+        let mut events = Vec::new();
+        let total = duration_of(self.duration());
+        if !self.playing || total == 0 {
+            return events;
+        }
+        if !self.entered {
+            self.enter(&mut events);
+        }
+        self.elapsed += (delta.as_microseconds() as f64 * f64::from(self.speed)).round() as i64;
+        // A ping-pong cycle shows the inner frames twice, the first and last ones once
+        let cycle = match self.mode {
+            PlaybackMode::Loop => total,
+            PlaybackMode::PingPong if self.frames.len() > 1 => {
+                let ends = duration_of(self.frames[0].1) +
+                           duration_of(self.frames[self.frames.len() - 1].1);
+                2 * total - ends
+            }
+            PlaybackMode::PingPong => total,
+            PlaybackMode::OneShot => 0,
+        };
+        if cycle > 0 && self.elapsed >= cycle {
+            self.elapsed %= cycle;
+            events.push(AnimationEvent::Looped);
+        }
+        loop {
+            let duration = duration_of(self.frames[self.current].1);
+            if self.elapsed < duration {
+                break;
+            }
+            if self.mode == PlaybackMode::OneShot && self.current == self.frames.len() - 1 {
+                self.elapsed = duration;
+                self.playing = false;
+                events.push(AnimationEvent::Finished);
+                break;
+            }
+            self.elapsed -= duration;
+            self.advance(&mut events);
+            self.enter(&mut events);
+        }
+        events
+    }
+
+    /// Go to the next frame in the playing order.
+    fn advance(&mut self, events: &mut Vec<AnimationEvent>) {
+        let last = self.frames.len() - 1;
+        match self.mode {
+            PlaybackMode::Loop | PlaybackMode::OneShot if self.current < last => self.current += 1,
+            PlaybackMode::Loop | PlaybackMode::OneShot => {
+                self.current = 0;
+                events.push(AnimationEvent::Looped);
+            }
+            PlaybackMode::PingPong => {
+                if self.backwards || self.current == last {
+                    self.backwards = self.current > 0;
+                    self.current = self.current.saturating_sub(1);
+                } else {
+                    self.current += 1;
+                }
+                if self.current == 0 {
+                    self.backwards = false;
+                    events.push(AnimationEvent::Looped);
+                }
+            }
+        }
+    }
+
+    /// Report the event of the current frame, if any.
+    fn enter(&mut self, events: &mut Vec<AnimationEvent>) {
+        self.entered = true;
+        if let Some(ref name) = self.events[self.current] {
+            events.push(AnimationEvent::Frame {
+                index: self.current,
+                name: name.clone(),
+            });
+        }
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Animation::new()
+    }
+}
+
+/// Return a duration in microseconds, negative durations counting as zero.
+fn duration_of(time: Time) -> i64 {
+    time.as_microseconds().max(0)
+}
+
+/// A `Sprite` whose texture rect is driven by an `Animation`.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{Animation, AnimatedSprite, IntRect, RenderTarget, RenderWindow,
+///                      Texture, Transformable};
+/// use sfml::system::{Clock, Time};
+/// use sfml::window::style;
+///
+/// let mut window = RenderWindow::new((800, 600), "Animation", style::CLOSE, &Default::default());
+/// let texture = Texture::from_file("explosion.png").unwrap();
+/// let frames: Vec<IntRect> = (0..8).map(|i| IntRect::new(i * 64, 0, 64, 64)).collect();
+///
+/// let mut explosion = AnimatedSprite::new(&texture, Animation::with_frames(&frames,
+///                                                                          Time::seconds(0.05)));
+/// explosion.set_position((400., 300.));
+/// explosion.animation_mut().play();
+///
+/// let mut clock = Clock::start();
+/// loop {
+///     explosion.update(clock.restart());
+///     window.draw(&explosion);
+///     window.display();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AnimatedSprite<'s> {
+    sprite: Sprite<'s>,
+    animation: Animation,
+}
+
+impl<'s> AnimatedSprite<'s> {
+    /// Create a sprite of `texture`, showing the current frame of `animation`.
+    pub fn new(texture: &'s TextureRef, animation: Animation) -> AnimatedSprite<'s> {
+        let mut sprite = Sprite::with_texture(texture);
+        if let Some(rect) = animation.current_rect() {
+            sprite.set_texture_rect(&rect);
+        }
+        AnimatedSprite { sprite, animation }
+    }
+
+    /// Advance the animation by `delta` and show its current frame.
+    ///
+    /// Return the events of `Animation::update`.
+    pub fn update(&mut self, delta: Time) -> Vec<AnimationEvent> {
+        let events = self.animation.update(delta);
+        self.sync();
+        events
+    }
+
+    /// Return the animation.
+    pub fn animation(&self) -> &Animation {
+        &self.animation
+    }
+
+    /// Return the animation, to change it or control its playback.
+    ///
+    /// Frame changes show on the sprite at the next `update`.
+    pub fn animation_mut(&mut self) -> &mut Animation {
+        &mut self.animation
+    }
+
+    /// Replace the animation, and show its current frame.
+    pub fn set_animation(&mut self, animation: Animation) {
+        self.animation = animation;
+        self.sync();
+    }
+
+    /// Return the sprite.
+    pub fn sprite(&self) -> &Sprite<'s> {
+        &self.sprite
+    }
+
+    /// Return the sprite, to change its texture or color.
+    ///
+    /// Its texture rect is replaced by the frames of the animation at the next `update`.
+    pub fn sprite_mut(&mut self) -> &mut Sprite<'s> {
+        &mut self.sprite
+    }
+
+    fn sync(&mut self) {
+        if let Some(rect) = self.animation.current_rect() {
+            if rect != self.sprite.texture_rect() {
+                self.sprite.set_texture_rect(&rect);
+            }
+        }
+    }
+}
+
+impl<'s> Drawable for AnimatedSprite<'s> {
+    fn draw<'se, 'tex, 'sh, 'shte>(&'se self,
+                                   target: &mut dyn RenderTarget,
+                                   states: RenderStates<'tex, 'sh, 'shte>)
+        where 'se: 'sh
+    {
+        target.draw_sprite(&self.sprite, states)
+    }
+}
+
+impl<'s> Transformable for AnimatedSprite<'s> {
+    fn set_position<P: Into<Vector2f>>(&mut self, position: P) {
+        self.sprite.set_position(position)
+    }
+    fn set_rotation(&mut self, angle: f32) {
+        self.sprite.set_rotation(angle)
+    }
+    fn set_scale<S: Into<Vector2f>>(&mut self, scale: S) {
+        self.sprite.set_scale(scale)
+    }
+    fn set_origin<O: Into<Vector2f>>(&mut self, origin: O) {
+        self.sprite.set_origin(origin)
+    }
+    fn position(&self) -> Vector2f {
+        self.sprite.position()
+    }
+    fn rotation(&self) -> f32 {
+        self.sprite.rotation()
+    }
+    fn get_scale(&self) -> Vector2f {
+        self.sprite.get_scale()
+    }
+    fn origin(&self) -> Vector2f {
+        self.sprite.origin()
+    }
+    fn move_<O: Into<Vector2f>>(&mut self, offset: O) {
+        self.sprite.move_(offset)
+    }
+    fn rotate(&mut self, angle: f32) {
+        self.sprite.rotate(angle)
+    }
+    fn scale<F: Into<Vector2f>>(&mut self, factors: F) {
+        self.sprite.scale(factors)
+    }
+    fn transform(&self) -> Transform {
+        self.sprite.transform()
+    }
+    fn inverse_transform(&self) -> Transform {
+        self.sprite.inverse_transform()
+    }
+}
+
+#[test]
+fn animation_timing() {
+    let ms = |ms: i64| Time::microseconds(ms * 1000);
+    let frames: Vec<IntRect> = (0..3).map(|i| IntRect::new(i * 10, 0, 10, 10)).collect();
+    let mut animation = Animation::with_frames(&frames, ms(100));
+    animation.set_frame_event(0, "start");
+    animation.set_frame_event(2, "hit");
+    assert_eq!(animation.update(ms(500)), []);
+    animation.play();
+    let frame = |index, name: &str| {
+        AnimationEvent::Frame {
+            index,
+            name: name.to_string(),
+        }
+    };
+    assert_eq!(animation.update(ms(250)), [frame(0, "start"), frame(2, "hit")]);
+    assert_eq!(animation.update(ms(60)), [AnimationEvent::Looped, frame(0, "start")]);
+    assert_eq!(animation.current_rect(), Some(IntRect::new(0, 0, 10, 10)));
+    // 10^12 loops of 300 ms are skipped at once, not stepped through
+    assert_eq!(animation.update(Time::microseconds(300_000_000_000_100_000)),
+               [AnimationEvent::Looped]);
+    assert_eq!(animation.current_frame(), 1);
+
+    animation.set_speed(2.);
+    animation.set_mode(PlaybackMode::PingPong);
+    animation.set_current_frame(0);
+    let mut visited = Vec::new();
+    for _ in 0..6 {
+        let _ = animation.update(ms(50));
+        visited.push(animation.current_frame());
+    }
+    assert_eq!(visited, [1, 2, 1, 0, 1, 2]);
+    // 10^12 ping-pongs of 400 ms are skipped at once, then 150 ms go back to the middle frame
+    assert_eq!(animation.update(Time::microseconds(200_000_000_000_075_000)),
+               [AnimationEvent::Looped]);
+    assert_eq!(animation.current_frame(), 1);
+
+    animation.set_speed(1.);
+    animation.set_mode(PlaybackMode::OneShot);
+    animation.stop();
+    animation.play();
+    assert_eq!(animation.update(ms(1000)),
+               [frame(0, "start"), frame(2, "hit"), AnimationEvent::Finished]);
+    assert!(animation.is_finished() && !animation.is_playing());
+    assert_eq!(animation.current_frame(), 2);
+    animation.play();
+    assert_eq!(animation.current_frame(), 0);
+}
@@ -1,5 +1,6 @@
 //! Available blending modes for drawing
 
+use graphics::Color;
 use graphics::csfml_graphics_sys as ffi;
 use system::raw_conv::{FromRaw, Raw};
 
@@ -45,7 +46,10 @@ use system::raw_conv::{FromRaw, Raw};
 /// In SFML, a blend mode can be specified every time you draw a `Drawable` object to
 /// a render target. It is part of the `RenderStates` compound that is passed to
 /// `RenderTarget::draw()`.
-#[derive(Clone, PartialEq, Eq, Debug, Copy)]
+///
+/// `BlendMode::blend` computes the result of a blend mode on the CPU, which is handy to test
+/// compositing logic without a GPU.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
 #[repr(C)]
 pub struct BlendMode {
     /// Source blending factor for the color channels.
@@ -109,6 +113,38 @@ pub const NONE: BlendMode = BlendMode {
     alpha_equation: Equation::Add,
 };
 
+/// "Premultiplied alpha" blend mode, alpha blending for colors whose RGB components are
+/// already multiplied by their alpha (see `Color::premultiplied`)
+pub const PREMULTIPLIED_ALPHA: BlendMode = BlendMode {
+    color_src_factor: Factor::One,
+    color_dst_factor: Factor::OneMinusSrcAlpha,
+    color_equation: Equation::Add,
+    alpha_src_factor: Factor::One,
+    alpha_dst_factor: Factor::OneMinusSrcAlpha,
+    alpha_equation: Equation::Add,
+};
+
+/// "Screen" blend mode, the inverse of multiplying the inverted colors: it only lightens
+pub const SCREEN: BlendMode = BlendMode {
+    color_src_factor: Factor::One,
+    color_dst_factor: Factor::OneMinusSrcColor,
+    color_equation: Equation::Add,
+    alpha_src_factor: Factor::One,
+    alpha_dst_factor: Factor::OneMinusSrcAlpha,
+    alpha_equation: Equation::Add,
+};
+
+/// "Subtract" blend mode, removes the source color weighted by its alpha from the destination,
+/// keeping the destination alpha
+pub const SUBTRACT: BlendMode = BlendMode {
+    color_src_factor: Factor::SrcAlpha,
+    color_dst_factor: Factor::One,
+    color_equation: Equation::ReverseSubtract,
+    alpha_src_factor: Factor::Zero,
+    alpha_dst_factor: Factor::One,
+    alpha_equation: Equation::Add,
+};
+
 impl Raw for BlendMode {
     type Raw = ffi::sfBlendMode;
 
@@ -128,7 +164,7 @@ impl FromRaw for BlendMode {
 ///
 /// The factors are mapped directly to their OpenGL equivalents, specified by
 /// `glBlendFunc()` or `glBlendFuncSeparate()`.
-#[derive(Clone, PartialEq, Eq, Debug, Copy)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
 #[repr(u32)]
 pub enum Factor {
     /// (0, 0, 0, 0)
@@ -157,7 +193,7 @@ pub enum Factor {
 ///
 /// The equations are mapped directly to their OpenGL equivalents, specified by
 /// `glBlendEquation()` or `glBlendEquationSeparate()`.
-#[derive(Clone, PartialEq, Eq, Debug, Copy)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
 #[repr(u32)]
 pub enum Equation {
     /// Pixel = Src * SrcFactor + Dst * DstFactor.
@@ -168,6 +204,66 @@ pub enum Equation {
     ReverseSubtract = 2,
 }
 
+/// Blending that keeps the smallest or the largest of each component.
+///
+/// SFML 2.6 adds these as the `Min` and `Max` blend equations, but the CSFML version this
+/// crate binds can't draw with them, so they are only available on the CPU, to test
+/// compositing logic.
+///
+/// ```
+/// # use sfml::graphics::Color;
+/// # use sfml::graphics::blend_mode::Extremum;
+/// assert_eq!(Extremum::Min.blend(Color::rgb(200, 100, 50), Color::rgb(100, 150, 200)),
+///            Color::rgb(100, 100, 50));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
+pub enum Extremum {
+    /// Pixel = min(Dst, Src).
+    Min,
+    /// Pixel = max(Dst, Src).
+    Max,
+}
+
+impl Extremum {
+    /// Blend the color `src` onto `dst`, component by component, alpha included.
+    pub fn blend(self, src: Color, dst: Color) -> Color {
+        let pick = |s: u8, d: u8| match self {
+            Extremum::Min => s.min(d),
+            Extremum::Max => s.max(d),
+        };
+        Color::rgba(pick(src.r, dst.r), pick(src.g, dst.g), pick(src.b, dst.b), pick(src.a, dst.a))
+    }
+}
+
+impl Factor {
+    /// Return the RGBA weights of the factor, between 0 and 1.
+    fn weights(self, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+        let one_minus = |c: [f32; 4]| [1. - c[0], 1. - c[1], 1. - c[2], 1. - c[3]];
+        match self {
+            Factor::Zero => [0.; 4],
+            Factor::One => [1.; 4],
+            Factor::SrcColor => src,
+            Factor::OneMinusSrcColor => one_minus(src),
+            Factor::DstColor => dst,
+            Factor::OneMinusDstColor => one_minus(dst),
+            Factor::SrcAlpha => [src[3]; 4],
+            Factor::OneMinusSrcAlpha => [1. - src[3]; 4],
+            Factor::DstAlpha => [dst[3]; 4],
+            Factor::OneMinusDstAlpha => [1. - dst[3]; 4],
+        }
+    }
+}
+
+impl Equation {
+    fn apply(self, src: f32, src_factor: f32, dst: f32, dst_factor: f32) -> f32 {
+        match self {
+            Equation::Add => src * src_factor + dst * dst_factor,
+            Equation::Subtract => src * src_factor - dst * dst_factor,
+            Equation::ReverseSubtract => dst * dst_factor - src * src_factor,
+        }
+    }
+}
+
 impl BlendMode {
     /// Create a new BlendMode
     pub fn new(col_src: Factor,
@@ -186,4 +282,59 @@ impl BlendMode {
             alpha_equation: alpha_equ,
         }
     }
+
+    /// Blend the color `src` onto `dst` on the CPU, as the GPU does when drawing.
+    ///
+    /// The components are converted to floating point numbers between 0 and 1,
+    /// and the result is clamped and rounded back to 8 bits.
+    ///
+    /// ```
+    /// # use sfml::graphics::{blend_mode, BlendMode, Color};
+    /// let half_red = Color::rgba(255, 0, 0, 128);
+    /// assert_eq!(BlendMode::default().blend(half_red, Color::blue()),
+    ///            Color::rgb(128, 0, 127));
+    /// assert_eq!(blend_mode::MULTIPLY.blend(Color::rgb(255, 128, 0), Color::white()),
+    ///            Color::rgb(255, 128, 0));
+    /// ```
+    pub fn blend(&self, src: Color, dst: Color) -> Color {
+        let unit = |c: Color| {
+            [c.r as f32 / 255., c.g as f32 / 255., c.b as f32 / 255., c.a as f32 / 255.]
+        };
+        let (src, dst) = (unit(src), unit(dst));
+        let color_src = self.color_src_factor.weights(src, dst);
+        let color_dst = self.color_dst_factor.weights(src, dst);
+        let alpha_src = self.alpha_src_factor.weights(src, dst);
+        let alpha_dst = self.alpha_dst_factor.weights(src, dst);
+        let mut result = [0u8; 4];
+        for (i, component) in result.iter_mut().enumerate() {
+            let value = if i < 3 {
+                self.color_equation.apply(src[i], color_src[i], dst[i], color_dst[i])
+            } else {
+                self.alpha_equation.apply(src[i], alpha_src[i], dst[i], alpha_dst[i])
+            };
+            *component = (value.clamp(0., 1.) * 255.).round() as u8;
+        }
+        Color::rgba(result[0], result[1], result[2], result[3])
+    }
+}
+
+#[test]
+fn cpu_blending() {
+    use std::collections::HashSet;
+
+    let dst = Color::rgba(100, 150, 200, 255);
+    let src = Color::rgba(200, 100, 50, 128);
+    assert_eq!(NONE.blend(src, dst), src);
+    assert_eq!(ALPHA.blend(src, dst), Color::rgb(150, 125, 125));
+    assert_eq!(PREMULTIPLIED_ALPHA.blend(src.premultiplied(), dst), Color::rgb(150, 125, 125));
+    assert_eq!(ADD.blend(src, dst), Color::rgb(200, 200, 225));
+    assert_eq!(SUBTRACT.blend(src, dst), Color::rgb(0, 100, 175));
+    assert_eq!(MULTIPLY.blend(src, dst), Color::rgba(78, 59, 39, 128));
+    assert_eq!(SCREEN.blend(Color::rgb(128, 128, 128), Color::rgb(128, 0, 255)),
+               Color::rgb(192, 128, 255));
+    assert_eq!(Extremum::Min.blend(src, dst), Color::rgba(100, 100, 50, 128));
+    assert_eq!(Extremum::Max.blend(src, dst), Color::rgba(200, 150, 200, 255));
+
+    let modes: HashSet<BlendMode> = [ALPHA, ADD, ALPHA, SCREEN].iter().cloned().collect();
+    assert_eq!(modes.len(), 3);
 }
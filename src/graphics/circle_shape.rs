@@ -122,10 +122,10 @@ impl<'s> Transformable for CircleShape<'s> {
         unsafe { ffi::sfCircleShape_scale(self.circle_shape, factors.into().raw()) }
     }
     fn transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfCircleShape_getTransform(self.circle_shape)) }
+        unsafe { Transform::from_raw(ffi::sfCircleShape_getTransform(self.circle_shape)) }
     }
     fn inverse_transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfCircleShape_getInverseTransform(self.circle_shape)) }
+        unsafe { Transform::from_raw(ffi::sfCircleShape_getInverseTransform(self.circle_shape)) }
     }
 }
 
@@ -1,5 +1,8 @@
 use graphics::csfml_graphics_sys as ffi;
+use std::error::Error;
+use std::fmt;
 use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::str::FromStr;
 use system::raw_conv::{FromRaw, Raw};
 
 /// Utility type for manpulating RGBA colors
@@ -113,6 +116,223 @@ impl Color {
     pub fn transparent() -> Color {
         Color::rgba(0, 0, 0, 0)
     }
+
+    /// Construct an opaque color from its hue, saturation and value
+    ///
+    /// # Arguments
+    /// * hue - Hue, in degrees (wrapped to 0 .. 360)
+    /// * saturation - Saturation (0 .. 1)
+    /// * value - Value (0 .. 1)
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Color {
+        let value = value.clamp(0., 1.);
+        let chroma = value * saturation.clamp(0., 1.);
+        Color::from_chroma(hue, chroma, value - chroma)
+    }
+
+    /// Construct an opaque color from its hue, saturation and lightness
+    ///
+    /// # Arguments
+    /// * hue - Hue, in degrees (wrapped to 0 .. 360)
+    /// * saturation - Saturation (0 .. 1)
+    /// * lightness - Lightness (0 .. 1)
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+        let lightness = lightness.clamp(0., 1.);
+        let chroma = (1. - (2. * lightness - 1.).abs()) * saturation.clamp(0., 1.);
+        Color::from_chroma(hue, chroma, lightness - chroma / 2.)
+    }
+
+    fn from_chroma(hue: f32, chroma: f32, min: f32) -> Color {
+        let sector = hue.rem_euclid(360.) / 60.;
+        let x = chroma * (1. - (sector % 2. - 1.).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.),
+            1 => (x, chroma, 0.),
+            2 => (0., chroma, x),
+            3 => (0., x, chroma),
+            4 => (x, 0., chroma),
+            _ => (chroma, 0., x),
+        };
+        Color::rgb(unit_to_u8(r + min), unit_to_u8(g + min), unit_to_u8(b + min))
+    }
+
+    /// Return the hue, in degrees (0 .. 360), and the maximum and minimum of the RGB
+    /// components (0 .. 1)
+    fn hue_max_min(self) -> (f32, f32, f32) {
+        let (r, g, b) = (self.r as f32 / 255., self.g as f32 / 255., self.b as f32 / 255.);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta == 0. {
+            0.
+        } else if max == r {
+            60. * ((g - b) / delta).rem_euclid(6.)
+        } else if max == g {
+            60. * ((b - r) / delta + 2.)
+        } else {
+            60. * ((r - g) / delta + 4.)
+        };
+        (hue, max, min)
+    }
+
+    /// Return the hue (in degrees, 0 .. 360), saturation (0 .. 1) and value (0 .. 1)
+    /// of the color
+    ///
+    /// The alpha component is ignored. Grays have a hue of 0.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let saturation = if max == 0. { 0. } else { (max - min) / max };
+        (hue, saturation, max)
+    }
+
+    /// Return the hue (in degrees, 0 .. 360), saturation (0 .. 1) and lightness (0 .. 1)
+    /// of the color
+    ///
+    /// The alpha component is ignored. Grays have a hue of 0.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let lightness = (max + min) / 2.;
+        let saturation = if max == min {
+            0.
+        } else {
+            (max - min) / (1. - (2. * lightness - 1.).abs())
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// Parse a color written in hexadecimal, as in CSS
+    ///
+    /// The accepted forms are `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`, the `#` being
+    /// optional. Colors without alpha are opaque. `str::parse` can be used as well.
+    ///
+    /// ```
+    /// # use sfml::graphics::Color;
+    /// assert_eq!(Color::from_hex("#ff8800cc"), Ok(Color::rgba(255, 136, 0, 204)));
+    /// assert_eq!("f80".parse(), Ok(Color::rgb(255, 136, 0)));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Color, ParseColorError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseColorError(()));
+        }
+        let value = |digit: u8| (digit as char).to_digit(16).unwrap_or(0) as u8;
+        let components: Vec<u8> = match digits.len() {
+            3 | 4 => digits.bytes().map(|d| value(d) * 17).collect(),
+            6 | 8 => digits.as_bytes().chunks(2).map(|p| value(p[0]) * 16 + value(p[1])).collect(),
+            _ => return Err(ParseColorError(())),
+        };
+        Ok(Color::rgba(components[0],
+                       components[1],
+                       components[2],
+                       components.get(3).cloned().unwrap_or(255)))
+    }
+
+    /// Return the color in hexadecimal, as `#rrggbb` if it is opaque or `#rrggbbaa` otherwise
+    pub fn to_hex(self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// Linearly interpolate between two colors, component-wise
+    ///
+    /// `t` is clamped to 0 .. 1, 0 giving `self` and 1 giving `other`.
+    /// The interpolation is done on the sRGB values, see `to_linear` to mix colors
+    /// in linear space instead.
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0., 1.);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color::rgba(mix(self.r, other.r),
+                    mix(self.g, other.g),
+                    mix(self.b, other.b),
+                    mix(self.a, other.a))
+    }
+
+    /// Convert the sRGB color to linear RGBA components (0 .. 1)
+    ///
+    /// Blending and interpolating in linear space avoids the dark fringes produced when
+    /// mixing sRGB values directly. The alpha component is already linear, it is only scaled.
+    pub fn to_linear(self) -> [f32; 4] {
+        [srgb_to_linear(self.r),
+         srgb_to_linear(self.g),
+         srgb_to_linear(self.b),
+         self.a as f32 / 255.]
+    }
+
+    /// Construct a sRGB color from linear RGBA components (0 .. 1), see `to_linear`
+    ///
+    /// Components out of range are clamped.
+    pub fn from_linear(components: [f32; 4]) -> Color {
+        Color::rgba(linear_to_srgb(components[0]),
+                    linear_to_srgb(components[1]),
+                    linear_to_srgb(components[2]),
+                    unit_to_u8(components[3]))
+    }
+
+    /// Return the color with its RGB components multiplied by its alpha
+    ///
+    /// Premultiplied colors are expected by the `BlendMode` using `One` as source factor.
+    pub fn premultiplied(self) -> Color {
+        let scale = |c: u8| (c as u32 * self.a as u32 + 127) / 255;
+        Color::rgba(scale(self.r) as u8, scale(self.g) as u8, scale(self.b) as u8, self.a)
+    }
+
+    /// Return the color with its RGB components divided by its alpha, undoing `premultiplied`
+    ///
+    /// As premultiplication loses precision, the result may differ slightly from the
+    /// original color. A fully transparent color gives `Color::transparent()`.
+    pub fn unpremultiplied(self) -> Color {
+        if self.a == 0 {
+            return Color::transparent();
+        }
+        let a = self.a as u32;
+        let scale = |c: u8| ((c as u32 * 255 + a / 2) / a).min(255) as u8;
+        Color::rgba(scale(self.r), scale(self.g), scale(self.b), self.a)
+    }
+}
+
+fn unit_to_u8(value: f32) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
+
+fn srgb_to_linear(component: u8) -> f32 {
+    let c = component as f32 / 255.;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(component: f32) -> u8 {
+    let c = component.clamp(0., 1.);
+    if c <= 0.003_130_8 {
+        unit_to_u8(c * 12.92)
+    } else {
+        unit_to_u8(1.055 * c.powf(1. / 2.4) - 0.055)
+    }
+}
+
+/// The error returned when parsing a hexadecimal color fails, see `Color::from_hex`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseColorError(());
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid color, expected #rgb, #rgba, #rrggbb or #rrggbbaa")
+    }
+}
+
+impl Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Parse a hexadecimal color, see `Color::from_hex`.
+    fn from_str(s: &str) -> Result<Color, ParseColorError> {
+        Color::from_hex(s)
+    }
 }
 
 impl From<u32> for Color {
@@ -177,3 +397,31 @@ impl MulAssign for Color {
         *self = *self * rhs;
     }
 }
+
+#[test]
+fn color_spaces() {
+    let orange = Color::rgb(255, 136, 0);
+    let (h, s, v) = orange.to_hsv();
+    assert!((h - 32.).abs() < 0.01 && s == 1. && v == 1.);
+    assert_eq!(Color::from_hsv(h, s, v), orange);
+    let (h, s, l) = orange.to_hsl();
+    assert_eq!(Color::from_hsl(h, s, l), orange);
+    assert_eq!(Color::from_hsv(-120., 1., 1.), Color::blue());
+    assert_eq!(Color::from_hsl(0., 0., 0.5), Color::rgb(128, 128, 128));
+
+    assert_eq!("#ff8800cc".parse(), Ok(Color::rgba(255, 136, 0, 204)));
+    assert_eq!(Color::from_hex("F80"), Ok(orange));
+    assert!(Color::from_hex("#ff88").is_ok());
+    assert!(Color::from_hex("#ff880").is_err() && Color::from_hex("#gg8800").is_err());
+    assert_eq!(orange.to_hex(), "#ff8800");
+    assert_eq!(Color::rgba(1, 2, 3, 4).to_hex(), "#01020304");
+
+    assert_eq!(Color::black().lerp(Color::white(), 0.5), Color::rgb(128, 128, 128));
+    assert_eq!(Color::black().lerp(Color::white(), 2.), Color::white());
+    assert_eq!(Color::from_linear(orange.to_linear()), orange);
+    assert!((Color::rgb(128, 128, 128).to_linear()[0] - 0.2158).abs() < 1e-3);
+
+    let translucent = Color::rgba(200, 100, 50, 128);
+    assert_eq!(translucent.premultiplied(), Color::rgba(100, 50, 25, 128));
+    assert_eq!(translucent.premultiplied().unpremultiplied(), Color::rgba(199, 100, 50, 128));
+}
@@ -1,18 +1,34 @@
+use assets::AssetSource;
 use csfml_system_sys::sfBool;
+use error::{self, Error, ErrorKind};
 use graphics::{Glyph, TextureRef};
 use graphics::csfml_graphics_sys as ffi;
-use inputstream::InputStream;
 use sf_bool_ext::SfBoolExt;
 use std::borrow::{Borrow, ToOwned};
+use std::collections::BTreeMap;
 use std::ffi::{CStr, CString};
+use std::fs;
 use std::io::{Read, Seek};
 use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard};
+use system::err;
 use system::raw_conv::{FromRaw, Raw, RawMut};
 
 /// Type for loading and manipulating character fonts
 #[derive(Debug)]
 pub struct Font {
     font: *mut ffi::sfFont,
+    source_size: usize,
+}
+
+/// The data of the fonts that weren't loaded from a file, by font pointer.
+///
+/// SFML reads glyphs from it lazily, and the copies made by `sfFont_copy` keep reading from
+/// it, so each copy gets its own entry and the data lives as long as one of them does.
+static FONT_DATA: Mutex<BTreeMap<usize, Arc<Vec<u8>>>> = Mutex::new(BTreeMap::new());
+
+fn font_data() -> MutexGuard<'static, BTreeMap<usize, Arc<Vec<u8>>>> {
+    FONT_DATA.lock().unwrap_or_else(|e| e.into_inner())
 }
 
 impl Deref for Font {
@@ -105,49 +121,112 @@ impl Font {
     /// # Arguments
     /// * filename -  Path of the font file to load
     ///
-    /// Return Some(Font) or None
-    pub fn from_file(filename: &str) -> Option<Font> {
+    /// Return the loaded Font, or an error describing why the file couldn't be loaded
+    pub fn from_file(filename: &str) -> error::Result<Font> {
+        error::check_readable(filename)?;
         let c_str = CString::new(filename.as_bytes()).unwrap();
-        let fnt = unsafe { ffi::sfFont_createFromFile(c_str.as_ptr()) };
+        let (fnt, message) = err::capture("Font::from_file",
+                                          || unsafe { ffi::sfFont_createFromFile(c_str.as_ptr()) });
         if fnt.is_null() {
-            None
+            Err(error::load_failure(filename, &[]).with_message(message))
         } else {
-            Some(Font { font: fnt })
+            Ok(Font {
+                   font: fnt,
+                   source_size: fs::metadata(filename).map_or(0, |m| m.len() as usize),
+               })
         }
     }
 
     /// Create a new font from a stream (a struct implementing Read and Seek)
     ///
+    /// SFML loads glyphs lazily, so the whole stream is read into memory first, and the
+    /// font doesn't borrow it.
+    ///
     /// # Arguments
     /// * stream - Your struct, implementing Read and Seek
     ///
-    /// Return Some(Font) or None
-    pub fn from_stream<T: Read + Seek>(stream: &mut T) -> Option<Font> {
-        let mut input_stream = InputStream::new(stream);
-        let fnt = unsafe { ffi::sfFont_createFromStream(&mut input_stream.0) };
-        if fnt.is_null() {
-            None
-        } else {
-            Some(Font { font: fnt })
-        }
+    /// Return the loaded Font, or an error if the stream failed or the data couldn't be decoded
+    pub fn from_stream<T: Read + Seek>(stream: &mut T) -> error::Result<Font> {
+        let mut data = Vec::new();
+        stream.rewind()?;
+        let _ = stream.read_to_end(&mut data)?;
+        Font::from_data("Font::from_stream", data)
+    }
+
+    /// Create a new font from a reader (a struct implementing Read and Seek)
+    ///
+    /// SFML loads glyphs lazily, so the whole reader is read into memory first, and the
+    /// data is kept by the font and its copies.
+    ///
+    /// # Arguments
+    /// * reader - Your struct, implementing Read and Seek
+    ///
+    /// Return the loaded Font, or an error if the reader failed or the data couldn't be decoded
+    ///
+    /// # Usage example
+    ///
+    /// ```no_run
+    /// use sfml::graphics::Font;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("examples/resources/sansation.ttf").unwrap();
+    /// let font = Font::from_reader(file).unwrap();
+    /// ```
+    pub fn from_reader<R: Read + Seek>(mut reader: R) -> error::Result<Font> {
+        Font::from_stream(&mut reader)
+    }
+
+    /// Create a new font from an asset
+    ///
+    /// The asset is read into memory, as glyphs are loaded lazily.
+    ///
+    /// # Arguments
+    /// * source - The directory or archive containing the asset
+    /// * path - Logical path of the font file in `source`
+    ///
+    /// Return the loaded Font, or an error describing why the asset couldn't be loaded
+    pub fn from_asset<S: AssetSource + ?Sized>(source: &S, path: &str) -> error::Result<Font> {
+        let data = source.read(path)?;
+        Font::from_data("Font::from_asset", data).map_err(|e| e.with_path(path))
     }
 
     /// Create a new font from memory
     ///
+    /// SFML loads glyphs lazily, so the data is copied, and the font doesn't borrow it.
+    ///
     /// # Arguments
     /// * memory -  The in-memory font file
     ///
-    /// Return Some(Font) or None
-    pub fn from_memory(memory: &[u8]) -> Option<Font> {
-        let fnt =
-            unsafe { ffi::sfFont_createFromMemory(memory.as_ptr() as *const _, memory.len()) };
+    /// Return the loaded Font, or an error if the data couldn't be decoded
+    pub fn from_memory(memory: &[u8]) -> error::Result<Font> {
+        Font::from_data("Font::from_memory", memory.to_vec())
+    }
+
+    fn from_data(origin: &'static str, data: Vec<u8>) -> error::Result<Font> {
+        let data = Arc::new(data);
+        let (fnt, message) = err::capture(origin, || unsafe {
+            ffi::sfFont_createFromMemory(data.as_ptr() as *const _, data.len())
+        });
         if fnt.is_null() {
-            None
+            Err(Error::new(ErrorKind::DecodeFailed).with_message(message))
         } else {
-            Some(Font { font: fnt })
+            let source_size = data.len();
+            let _ = font_data().insert(fnt as usize, data);
+            Ok(Font {
+                   font: fnt,
+                   source_size,
+               })
         }
     }
 
+    /// Get the size, in bytes, of the font file the font was loaded from
+    ///
+    /// Return 0 for copies of fonts loaded from a file made from a `FontRef`, whose file
+    /// is unknown
+    pub fn source_size(&self) -> usize {
+        self.source_size
+    }
+
     /// Get the texture containing the glyphs of a given size in a font
     ///
     /// # Arguments
@@ -180,7 +259,16 @@ impl ToOwned for FontRef {
         if fnt.is_null() {
             panic!("Not enough memory to clone Font")
         } else {
-            Font { font: fnt }
+            let mut font_data = font_data();
+            let data = font_data.get(&(self.raw() as usize)).cloned();
+            let source_size = data.as_ref().map_or(0, |data| data.len());
+            if let Some(data) = data {
+                let _ = font_data.insert(fnt as usize, data);
+            }
+            Font {
+                font: fnt,
+                source_size,
+            }
         }
     }
 }
@@ -188,7 +276,9 @@ impl ToOwned for FontRef {
 impl Clone for Font {
     /// Return a new Font or panic! if there is not enough memory
     fn clone(&self) -> Font {
-        (**self).to_owned()
+        let mut font = (**self).to_owned();
+        font.source_size = self.source_size;
+        font
     }
 }
 
@@ -209,6 +299,21 @@ impl RawMut for Font {
 
 impl Drop for Font {
     fn drop(&mut self) {
+        // Locked until the entry is gone, so that a new font at the same address can't lose it
+        let mut font_data = font_data();
         unsafe { ffi::sfFont_destroy(self.font) }
+        let _ = font_data.remove(&(self.font as usize));
     }
 }
+
+#[test]
+fn copies_keep_the_data() {
+    let file = ::std::fs::File::open("examples/resources/sansation.ttf").unwrap();
+    let font = Font::from_reader(file).unwrap();
+    let copy = (*font).to_owned();
+    let size = font.source_size();
+    drop(font);
+    assert_eq!(copy.source_size(), size);
+    let glyph = copy.glyph('A' as u32, 30, false, 0.);
+    assert!(glyph.bounds.width > 0.);
+}
@@ -156,7 +156,7 @@ pub struct Mat3(pub [f32; 9]);
 
 impl From<::graphics::Transform> for Mat3 {
     fn from(src: ::graphics::Transform) -> Self {
-        Mat3(src.0.matrix)
+        Mat3(*src.as_array())
     }
 }
 
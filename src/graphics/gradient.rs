@@ -0,0 +1,281 @@
+use graphics::{Color, PrimitiveType, Shape, Vertex, VertexArray};
+use system::Vector2f;
+
+/// How the colors of a `Gradient` are laid out in space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientStyle {
+    /// The colors change along the line from `start` (offset 0) to `end` (offset 1),
+    /// and are constant perpendicularly to it.
+    Linear {
+        /// Point at offset 0.
+        start: Vector2f,
+        /// Point at offset 1.
+        end: Vector2f,
+    },
+    /// The colors change with the distance to `center` (offset 0), up to `radius` (offset 1).
+    Radial {
+        /// Point at offset 0.
+        center: Vector2f,
+        /// Distance to the center at offset 1.
+        radius: f32,
+    },
+}
+
+impl GradientStyle {
+    /// Return the gradient offset of a point, 0 and 1 being the ends of the gradient.
+    ///
+    /// Points beyond the ends give offsets outside of 0 .. 1.
+    pub fn offset(&self, point: Vector2f) -> f32 {
+        match *self {
+            GradientStyle::Linear { start, end } => {
+                let direction = end - start;
+                let length_sq = direction.length_sq();
+                if length_sq == 0. {
+                    0.
+                } else {
+                    (point - start).dot(direction) / length_sq
+                }
+            }
+            GradientStyle::Radial { center, radius } => {
+                if radius <= 0. {
+                    0.
+                } else {
+                    point.distance(center) / radius
+                }
+            }
+        }
+    }
+}
+
+/// A color ramp made of colors at given offsets.
+///
+/// Between two stops, the color is linearly interpolated. Before the first stop and after
+/// the last one, the color of the nearest stop is used. Two stops at the same offset make
+/// a sharp transition.
+///
+/// By default the colors are mixed on their sRGB values, like most image editors do.
+/// With linear mixing, they are mixed in linear space, which avoids the dark band between
+/// saturated colors (e.g. red to green) at the cost of a brighter look.
+///
+/// A gradient can color the vertices of an existing `VertexArray`, or build the vertices
+/// of a shape filled with the gradient.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{Color, ConvexShape, Gradient, GradientStyle, RenderTarget,
+///                      RenderWindow, Transformable};
+/// use sfml::system::Vector2f;
+/// use sfml::window::style;
+///
+/// let mut window = RenderWindow::new((800, 600), "Gradient", style::CLOSE, &Default::default());
+///
+/// let mut sunset = Gradient::new(Color::rgb(255, 200, 0), Color::rgb(80, 0, 120));
+/// sunset.add_stop(0.4, Color::rgb(255, 80, 0));
+///
+/// let mut shape = ConvexShape::new(3);
+/// shape.set_point(0, Vector2f::new(0., 0.));
+/// shape.set_point(1, Vector2f::new(200., 0.));
+/// shape.set_point(2, Vector2f::new(100., 150.));
+/// shape.set_position((300., 200.));
+///
+/// let style = GradientStyle::Linear {
+///     start: Vector2f::new(0., 0.),
+///     end: Vector2f::new(0., 150.),
+/// };
+/// let vertices = sunset.shape_vertices(&shape, &style, 8);
+/// window.draw(&vertices);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+    linear_mixing: bool,
+}
+
+impl Gradient {
+    /// Create a gradient going from `start` at offset 0 to `end` at offset 1.
+    pub fn new(start: Color, end: Color) -> Gradient {
+        Gradient::with_stops(&[(0., start), (1., end)])
+    }
+
+    /// Create a gradient from `(offset, color)` stops, in any order.
+    ///
+    /// Offsets are clamped to 0 .. 1. A gradient without stops is transparent.
+    pub fn with_stops(stops: &[(f32, Color)]) -> Gradient {
+        let mut gradient = Gradient {
+            stops: Vec::with_capacity(stops.len()),
+            linear_mixing: false,
+        };
+        for &(offset, color) in stops {
+            gradient.add_stop(offset, color);
+        }
+        gradient
+    }
+
+    /// Add a color stop at `offset`, clamped to 0 .. 1.
+    ///
+    /// If there already are stops at this offset, the new one comes after them.
+    pub fn add_stop(&mut self, offset: f32, color: Color) {
+        let offset = offset.clamp(0., 1.);
+        let index = self.stops.iter().take_while(|s| s.0 <= offset).count();
+        self.stops.insert(index, (offset, color));
+    }
+
+    /// Return the `(offset, color)` stops, ordered by offset.
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Enable or disable mixing the colors in linear space instead of on their sRGB values.
+    pub fn set_linear_mixing(&mut self, linear_mixing: bool) {
+        self.linear_mixing = linear_mixing;
+    }
+
+    /// Tell whether the colors are mixed in linear space.
+    pub fn is_linear_mixing(&self) -> bool {
+        self.linear_mixing
+    }
+
+    /// Return the color of the gradient at `offset`, clamped to 0 .. 1.
+    pub fn color_at(&self, offset: f32) -> Color {
+        let offset = offset.clamp(0., 1.);
+        let next = self.stops.iter().take_while(|s| s.0 <= offset).count();
+        if next == 0 {
+            return self.stops.first().map_or(Color::transparent(), |s| s.1);
+        }
+        let (from_offset, from) = self.stops[next - 1];
+        let (to_offset, to) = match self.stops.get(next) {
+            Some(&stop) => stop,
+            None => return from,
+        };
+        let t = (offset - from_offset) / (to_offset - from_offset);
+        if self.linear_mixing {
+            let mut mixed = from.to_linear();
+            for (component, &to) in mixed.iter_mut().zip(&to.to_linear()) {
+                *component += (to - *component) * t;
+            }
+            Color::from_linear(mixed)
+        } else {
+            from.lerp(to, t)
+        }
+    }
+
+    /// Set the color of each vertex from its position.
+    pub fn fill_vertices(&self, vertices: &mut [Vertex], style: &GradientStyle) {
+        for vertex in vertices {
+            vertex.color = self.color_at(style.offset(vertex.position));
+        }
+    }
+
+    /// Set the color of each vertex of the array from its position.
+    ///
+    /// The vertices keep their positions and texture coordinates, so the array should have
+    /// enough vertices for the gradient to look smooth: colors are only interpolated linearly
+    /// between vertices.
+    pub fn fill_vertex_array(&self, vertex_array: &mut VertexArray, style: &GradientStyle) {
+        for i in 0..vertex_array.vertex_count() {
+            let position = vertex_array[i].position;
+            vertex_array[i].color = self.color_at(style.offset(position));
+        }
+    }
+
+    /// Return triangles covering the convex polygon `points`, colored with the gradient.
+    ///
+    /// The polygon is split into triangles from its center to each of its edges, and each
+    /// triangle into `bands` bands (at least 1), as colors are only interpolated linearly
+    /// between vertices. A two-stop linear gradient looks right with a single band, radial
+    /// gradients and gradients with more stops need more.
+    ///
+    /// The vertices are meant to be drawn as `PrimitiveType::Triangles`.
+    pub fn polygon_vertices(&self,
+                            points: &[Vector2f],
+                            style: &GradientStyle,
+                            bands: u32)
+                            -> Vec<Vertex> {
+        if points.len() < 3 {
+            return Vec::new();
+        }
+        let sum = points.iter().fold(Vector2f::new(0., 0.), |acc, &p| acc + p);
+        let center = sum / points.len() as f32;
+        let bands = bands.max(1);
+        let vertex = |position: Vector2f| {
+            Vertex::with_pos_color(position, self.color_at(style.offset(position)))
+        };
+
+        let mut vertices = Vec::with_capacity(points.len() * (bands as usize * 6 - 3));
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            for band in 0..bands {
+                let inner = band as f32 / bands as f32;
+                let outer = (band + 1) as f32 / bands as f32;
+                let inner_a = vertex(center.lerp(a, inner));
+                let outer_a = vertex(center.lerp(a, outer));
+                let outer_b = vertex(center.lerp(b, outer));
+                vertices.extend_from_slice(&[inner_a, outer_a, outer_b]);
+                // The innermost band is a single triangle
+                if band > 0 {
+                    let inner_b = vertex(center.lerp(b, inner));
+                    vertices.extend_from_slice(&[inner_a, outer_b, inner_b]);
+                }
+            }
+        }
+        vertices
+    }
+
+    /// Return the vertices of a shape filled with the gradient, see `polygon_vertices`.
+    ///
+    /// `style` is in the local coordinates of the shape (the coordinates of its points),
+    /// the vertices are transformed by the shape's transform so that the array can be drawn
+    /// in place of the shape. The outline and texture of the shape are not reproduced.
+    pub fn shape_vertices<'s, S: Shape<'s>>(&self,
+                                            shape: &S,
+                                            style: &GradientStyle,
+                                            bands: u32)
+                                            -> VertexArray {
+        let points: Vec<Vector2f> = (0..shape.point_count()).map(|i| shape.point(i)).collect();
+        let transform = shape.transform();
+        let mut vertex_array = VertexArray::default();
+        vertex_array.set_primitive_type(PrimitiveType::Triangles);
+        for mut vertex in self.polygon_vertices(&points, style, bands) {
+            vertex.position = transform.transform_point(&vertex.position);
+            vertex_array.append(&vertex);
+        }
+        vertex_array
+    }
+}
+
+#[test]
+fn gradient_colors() {
+    let mut gradient = Gradient::with_stops(&[(1., Color::blue()), (0., Color::red())]);
+    gradient.add_stop(0.5, Color::green());
+    gradient.add_stop(0.5, Color::white());
+    assert_eq!(gradient.color_at(-1.), Color::red());
+    assert_eq!(gradient.color_at(0.25), Color::rgb(128, 128, 0));
+    assert_eq!(gradient.color_at(0.5), Color::white());
+    assert_eq!(gradient.color_at(0.75), Color::rgb(128, 128, 255));
+    assert_eq!(gradient.color_at(2.), Color::blue());
+    gradient.set_linear_mixing(true);
+    assert_eq!(gradient.color_at(0.25), Color::rgb(188, 188, 0));
+    assert_eq!(Gradient::with_stops(&[]).color_at(0.5), Color::transparent());
+
+    let linear = GradientStyle::Linear {
+        start: Vector2f::new(0., 0.),
+        end: Vector2f::new(0., 10.),
+    };
+    assert_eq!(linear.offset(Vector2f::new(42., 5.)), 0.5);
+    let radial = GradientStyle::Radial {
+        center: Vector2f::new(1., 1.),
+        radius: 2.,
+    };
+    assert_eq!(radial.offset(Vector2f::new(1., 4.)), 1.5);
+
+    let square = [Vector2f::new(0., 0.),
+                  Vector2f::new(10., 0.),
+                  Vector2f::new(10., 10.),
+                  Vector2f::new(0., 10.)];
+    let vertices = Gradient::new(Color::black(), Color::white())
+        .polygon_vertices(&square, &linear, 3);
+    assert_eq!(vertices.len(), 4 * (3 + 2 * 6));
+    assert_eq!(vertices[0].color, Color::rgb(128, 128, 128));
+    assert!(vertices.iter().all(|v| v.color.r == (v.position.y * 25.5).round() as u8));
+}
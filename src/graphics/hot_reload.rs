@@ -0,0 +1,245 @@
+use error;
+use graphics::{Font, Image, Shader, Texture};
+use std::fmt;
+use std::fs;
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant, SystemTime};
+
+type Reloader<T> = Box<dyn FnMut(&mut T) -> error::Result<()>>;
+
+/// A resource that is reloaded when the files it was loaded from change.
+///
+/// Changes are detected by polling the modification time of the files: call `poll`
+/// regularly (e.g. once per frame), it only touches the filesystem once per poll interval.
+/// When a reload fails (an image that is still being written, a shader that doesn't
+/// compile..), the error is reported and the previous version of the resource is kept.
+///
+/// `HotReload` dereferences to the resource, so it can be used wherever the resource is.
+/// As the resource may be replaced by a reload, it can't be borrowed across calls to `poll`:
+/// sprites, texts and render states must borrow it again after polling.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{HotReload, Sprite};
+///
+/// let mut texture = HotReload::texture("resources/logo.png").unwrap();
+/// loop {
+///     if let Err(e) = texture.poll() {
+///         println!("Keeping the previous texture: {}", e);
+///     }
+///     let sprite = Sprite::with_texture(&texture);
+///     // draw the sprite..
+/// #   break;
+/// }
+/// ```
+pub struct HotReload<T> {
+    resource: T,
+    files: Vec<(String, Option<SystemTime>)>,
+    reload: Reloader<T>,
+    interval: Duration,
+    last_check: Instant,
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl<T> HotReload<T> {
+    /// Watch the files of an already loaded resource.
+    ///
+    /// `reload` is called with the current resource when one of `files` changes. It must leave
+    /// the resource untouched when it fails, so that the previous version is kept.
+    pub fn new<F>(resource: T, files: &[&str], reload: F) -> HotReload<T>
+        where F: FnMut(&mut T) -> error::Result<()> + 'static
+    {
+        HotReload {
+            resource,
+            files: files.iter().map(|&f| (f.to_owned(), modified(f))).collect(),
+            reload: Box::new(reload),
+            interval: Duration::from_millis(250),
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Set how often `poll` checks the files for changes. Defaults to 250 milliseconds.
+    pub fn set_poll_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Return the minimum time between two checks of the files.
+    pub fn poll_interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Reload the resource if one of its files changed since the last reload.
+    ///
+    /// Return `Ok(true)` if the resource was reloaded, `Ok(false)` if nothing changed or the
+    /// poll interval didn't elapse yet, or the error of a failed reload.
+    /// A failed reload isn't retried until the files change again.
+    pub fn poll(&mut self) -> error::Result<bool> {
+        if self.last_check.elapsed() < self.interval {
+            return Ok(false);
+        }
+        self.last_check = Instant::now();
+        let mut changed = false;
+        for &mut (ref path, ref mut stamp) in &mut self.files {
+            let current = modified(path);
+            if current != *stamp {
+                *stamp = current;
+                changed = true;
+            }
+        }
+        if changed {
+            self.reload().map(|()| true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Reload the resource now, whether its files changed or not.
+    ///
+    /// On failure, the previous version of the resource is kept.
+    pub fn reload(&mut self) -> error::Result<()> {
+        (self.reload)(&mut self.resource)
+    }
+
+    /// Return the paths of the watched files.
+    pub fn files(&self) -> Vec<&str> {
+        self.files.iter().map(|f| &f.0[..]).collect()
+    }
+
+    /// Stop watching and return the resource.
+    pub fn into_inner(self) -> T {
+        self.resource
+    }
+}
+
+impl HotReload<Texture> {
+    /// Load a texture from a file and watch it for changes.
+    ///
+    /// Reloads update the texture in place when the size of the image didn't change,
+    /// otherwise a new texture is created with the same smooth, repeated and sRGB settings.
+    pub fn texture(path: &str) -> error::Result<HotReload<Texture>> {
+        let texture = Texture::from_file(path)?;
+        let owned = path.to_owned();
+        Ok(HotReload::new(texture, &[path], move |texture| {
+            let image = Image::from_file(&owned)?;
+            if image.size() == texture.size() {
+                texture.update_from_image(&image, 0, 0);
+            } else {
+                let mut new = Texture::from_image(&image).map_err(|e| e.with_path(&owned[..]))?;
+                new.set_smooth(texture.is_smooth());
+                new.set_repeated(texture.is_repeated());
+                new.set_srgb(texture.is_srgb());
+                *texture = new;
+            }
+            Ok(())
+        }))
+    }
+}
+
+impl HotReload<Font> {
+    /// Load a font from a file and watch it for changes.
+    pub fn font(path: &str) -> error::Result<HotReload<Font>> {
+        let font = Font::from_file(path)?;
+        let owned = path.to_owned();
+        Ok(HotReload::new(font, &[path], move |font| {
+            *font = Font::from_file(&owned)?;
+            Ok(())
+        }))
+    }
+}
+
+impl<'te> HotReload<Shader<'te>> {
+    /// Load a shader from files and watch them for changes.
+    ///
+    /// See `Shader::from_file` for the arguments. A reloaded shader starts with no uniform set,
+    /// uniforms must be set again after `poll` reported a reload.
+    pub fn shader(vertex: Option<&str>,
+                  geometry: Option<&str>,
+                  fragment: Option<&str>)
+                  -> error::Result<HotReload<Shader<'te>>> {
+        let shader = Shader::from_file(vertex, geometry, fragment)?;
+        let files: Vec<&str> = vertex.iter().chain(&geometry).chain(&fragment).cloned().collect();
+        let (vertex, geometry, fragment) = (vertex.map(str::to_owned),
+                                            geometry.map(str::to_owned),
+                                            fragment.map(str::to_owned));
+        Ok(HotReload::new(shader, &files, move |shader| {
+            *shader = Shader::from_file(vertex.as_ref().map(|s| &s[..]),
+                                        geometry.as_ref().map(|s| &s[..]),
+                                        fragment.as_ref().map(|s| &s[..]))?;
+            Ok(())
+        }))
+    }
+}
+
+impl<T> Deref for HotReload<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.resource
+    }
+}
+
+impl<T> DerefMut for HotReload<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.resource
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HotReload<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HotReload")
+            .field("resource", &self.resource)
+            .field("files", &self.files())
+            .finish()
+    }
+}
+
+#[test]
+fn reload_on_change() {
+    use error::{Error, ErrorKind};
+    use std::fs::File;
+    use std::io::Write;
+    use std::time::UNIX_EPOCH;
+
+    let name = format!("rust-sfml-hot-reload-{}", ::std::process::id());
+    let path = ::std::env::temp_dir().join(name).to_str().unwrap().to_owned();
+    // Writes the file with an explicit modification time, filesystem clocks can be coarse
+    let write = |text: &str, seconds: u64| {
+        let mut file = File::create(&path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+        file.set_modified(UNIX_EPOCH + Duration::from_secs(seconds)).unwrap();
+    };
+    write("1", 1000);
+
+    let watched = path.clone();
+    let mut number = HotReload::new(1, &[&path], move |number: &mut u32| {
+        let text = fs::read_to_string(&watched)?;
+        *number = text.parse()
+            .map_err(|_| Error::new(ErrorKind::DecodeFailed).with_path(&watched[..]))?;
+        Ok(())
+    });
+    number.set_poll_interval(Duration::from_secs(0));
+    assert!(!number.poll().unwrap());
+
+    write("2", 2000);
+    assert!(number.poll().unwrap());
+    assert_eq!(*number, 2);
+
+    // A failed reload keeps the previous value, and isn't retried until the file changes
+    write("two", 3000);
+    assert_eq!(number.poll().unwrap_err().kind(), ErrorKind::DecodeFailed);
+    assert_eq!(*number, 2);
+    assert!(!number.poll().unwrap());
+
+    // Removing the file is a change too
+    fs::remove_file(&path).unwrap();
+    assert_eq!(number.poll().unwrap_err().kind(), ErrorKind::FileNotFound);
+    assert_eq!(*number, 2);
+    write("3", 4000);
+    assert!(number.poll().unwrap());
+    assert_eq!(number.into_inner(), 3);
+    fs::remove_file(&path).unwrap();
+}
@@ -1,12 +1,17 @@
+use assets::AssetSource;
 use csfml_system_sys::sfBool;
-use graphics::{Color, IntRect};
+use error::{self, Error, ErrorKind, IMAGE_LOAD_FORMATS, IMAGE_SAVE_FORMATS};
+use graphics::{BlendMode, Color, IntRect};
 use graphics::csfml_graphics_sys as ffi;
+use graphics::ImageFormat;
+use graphics::image_encoding;
 use inputstream::InputStream;
 use sf_bool_ext::SfBoolExt;
 use std::ffi::CString;
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, Write};
 use std::slice;
 use system::Vector2u;
+use system::err;
 use system::raw_conv::{FromRaw, Raw};
 
 /// Loading, manipulating and saving images.
@@ -36,14 +41,16 @@ impl Image {
     /// # Arguments
     /// * stream - Your struct, implementing Read and Seek
     ///
-    /// Return Some(Image) or None
-    pub fn from_stream<T: Read + Seek>(stream: &mut T) -> Option<Image> {
+    /// Return the loaded Image, or an error if the stream failed or the data couldn't be decoded
+    pub fn from_stream<T: Read + Seek>(stream: &mut T) -> error::Result<Image> {
         let mut input_stream = InputStream::new(stream);
-        let image = unsafe { ffi::sfImage_createFromStream(&mut input_stream.0) };
+        let (image, message) = err::capture("Image::from_stream", || unsafe {
+            ffi::sfImage_createFromStream(input_stream.raw_mut())
+        });
         if image.is_null() {
-            None
+            Err(input_stream.load_failure(message))
         } else {
-            Some(Image { image: image })
+            Ok(Image { image: image })
         }
     }
 
@@ -54,13 +61,15 @@ impl Image {
     /// # Arguments
     /// * mem - Pointer to the file data in memory
     ///
-    /// Return Some(Image) or None
-    pub fn from_memory(mem: &[u8]) -> Option<Image> {
-        let image = unsafe { ffi::sfImage_createFromMemory(mem.as_ptr() as *const _, mem.len()) };
+    /// Return the loaded Image, or an error if the data couldn't be decoded
+    pub fn from_memory(mem: &[u8]) -> error::Result<Image> {
+        let (image, message) = err::capture("Image::from_memory", || unsafe {
+            ffi::sfImage_createFromMemory(mem.as_ptr() as *const _, mem.len())
+        });
         if image.is_null() {
-            None
+            Err(Error::new(ErrorKind::DecodeFailed).with_message(message))
         } else {
-            Some(Image { image: image })
+            Ok(Image { image: image })
         }
     }
 
@@ -71,16 +80,28 @@ impl Image {
     /// * height - Height of the image
     /// * color - Fill color
     ///
-    /// Return Some(Image) or None
-    pub fn from_color(width: u32, height: u32, color: &Color) -> Option<Image> {
+    /// Return the new Image, or an error if it couldn't be allocated
+    pub fn from_color(width: u32, height: u32, color: &Color) -> error::Result<Image> {
         let image = unsafe { ffi::sfImage_createFromColor(width, height, color.raw()) };
         if image.is_null() {
-            None
+            Err(Error::new(ErrorKind::ResourceCreation))
         } else {
-            Some(Image { image: image })
+            Ok(Image { image: image })
         }
     }
 
+    /// Create an image from an asset
+    ///
+    /// # Arguments
+    /// * source - The directory or archive containing the asset
+    /// * path - Logical path of the image in `source`
+    ///
+    /// Return the loaded Image, or an error if the asset couldn't be read or decoded
+    pub fn from_asset<S: AssetSource + ?Sized>(source: &S, path: &str) -> error::Result<Image> {
+        let data = source.read(path)?;
+        Image::from_memory(&data).map_err(|e| e.with_path(path))
+    }
+
     /// Create an image from a file on disk
     ///
     /// The supported image formats are bmp, png, tga, jpg, gif,
@@ -91,14 +112,17 @@ impl Image {
     /// # Arguments
     /// * filename - Path of the image file to load
     ///
-    /// Return Some(Image) or None
-    pub fn from_file(filename: &str) -> Option<Image> {
+    /// Return the loaded Image, or an error describing why the file couldn't be loaded
+    pub fn from_file(filename: &str) -> error::Result<Image> {
+        error::check_readable(filename)?;
         let c_filename = CString::new(filename.as_bytes()).unwrap();
-        let image = unsafe { ffi::sfImage_createFromFile(c_filename.as_ptr()) };
+        let (image, message) = err::capture("Image::from_file", || unsafe {
+            ffi::sfImage_createFromFile(c_filename.as_ptr())
+        });
         if image.is_null() {
-            None
+            Err(error::load_failure(filename, IMAGE_LOAD_FORMATS).with_message(message))
         } else {
-            Some(Image { image: image })
+            Ok(Image { image: image })
         }
     }
 
@@ -113,13 +137,13 @@ impl Image {
     /// * height - Height of the image
     /// * pixels - Vector of pixels to copy to the image
     ///
-    /// Return Some(Image) or None
-    pub fn create_from_pixels(width: u32, height: u32, pixels: &[u8]) -> Option<Image> {
+    /// Return the new Image, or an error if it couldn't be allocated
+    pub fn create_from_pixels(width: u32, height: u32, pixels: &[u8]) -> error::Result<Image> {
         let image = unsafe { ffi::sfImage_createFromPixels(width, height, pixels.as_ptr()) };
         if image.is_null() {
-            None
+            Err(Error::new(ErrorKind::ResourceCreation))
         } else {
-            Some(Image { image: image })
+            Ok(Image { image: image })
         }
     }
 
@@ -133,10 +157,49 @@ impl Image {
     /// # Arguments
     /// * filename - Path of the file to save
     ///
-    /// Return true if saving was successful
-    pub fn save_to_file(&self, filename: &str) -> bool {
+    /// Return an error describing the failure if the image couldn't be saved
+    pub fn save_to_file(&self, filename: &str) -> error::Result<()> {
         let c_str = CString::new(filename.as_bytes()).unwrap();
-        unsafe { ffi::sfImage_saveToFile(self.image, c_str.as_ptr()) }.to_bool()
+        let (saved, message) = err::capture("Image::save_to_file", || unsafe {
+            ffi::sfImage_saveToFile(self.image, c_str.as_ptr())
+        });
+        if saved.to_bool() {
+            Ok(())
+        } else {
+            Err(error::save_failure(filename, IMAGE_SAVE_FORMATS).with_message(message))
+        }
+    }
+
+    /// Encode the image in memory, in the given format.
+    ///
+    /// Unlike `save_to_file`, the encoding is done by this crate rather than by SFML,
+    /// so the output differs slightly from the files SFML writes.
+    ///
+    /// Return an error of kind `EncodeFailed` if the image is empty or too large for the
+    /// format.
+    ///
+    /// # Usage example
+    ///
+    /// ```no_run
+    /// use sfml::graphics::{Image, ImageFormat};
+    ///
+    /// let image = Image::from_file("screenshot.png").unwrap();
+    /// let jpeg = image.save_to_memory(ImageFormat::Jpeg { quality: 85 }).unwrap();
+    /// println!("{} bytes", jpeg.len());
+    /// ```
+    pub fn save_to_memory(&self, format: ImageFormat) -> error::Result<Vec<u8>> {
+        let size = self.size();
+        image_encoding::encode(self.pixel_data(), size.x, size.y, format)
+    }
+
+    /// Encode the image in the given format, and write it to `writer`.
+    ///
+    /// The image is encoded in memory first, see `save_to_memory`.
+    /// Return an error of kind `Io` if writing fails.
+    pub fn write_to<W: Write>(&self, writer: &mut W, format: ImageFormat) -> error::Result<()> {
+        let data = self.save_to_memory(format)?;
+        writer.write_all(&data)?;
+        Ok(())
     }
 
     /// Return the size of an image
@@ -190,14 +253,46 @@ impl Image {
 
     /// Return the memory buffer of this image.
     pub fn pixel_data(&self) -> &[u8] {
+        let size = self.size();
+        if size.x == 0 || size.y == 0 {
+            return &[];
+        }
         unsafe {
-            let size = self.size();
             let pixels = ffi::sfImage_getPixelsPtr(self.image);
 
             slice::from_raw_parts(pixels, (size.x * size.y * 4) as usize)
         }
     }
 
+    /// Return the memory buffer of this image, for modifying it in place.
+    ///
+    /// The buffer holds the RGBA components of the pixels, row by row.
+    pub fn pixel_data_mut(&mut self) -> &mut [u8] {
+        let size = self.size();
+        if size.x == 0 || size.y == 0 {
+            return &mut [];
+        }
+        unsafe {
+            // The buffer belongs to the image, CSFML only returns it as const
+            let pixels = ffi::sfImage_getPixelsPtr(self.image) as *mut u8;
+
+            slice::from_raw_parts_mut(pixels, (size.x * size.y * 4) as usize)
+        }
+    }
+
+    /// Return the pixels of this image, row by row.
+    pub fn pixels(&self) -> &[Color] {
+        let data = self.pixel_data();
+        // Color is made of 4 u8 and has the same layout as the RGBA components
+        unsafe { slice::from_raw_parts(data.as_ptr() as *const Color, data.len() / 4) }
+    }
+
+    /// Return the pixels of this image row by row, for modifying them in place.
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        let data = self.pixel_data_mut();
+        unsafe { slice::from_raw_parts_mut(data.as_mut_ptr() as *mut Color, data.len() / 4) }
+    }
+
     /// Flip an image horizontally (left <-> right)
     pub fn flip_horizontally(&mut self) {
         unsafe { ffi::sfImage_flipHorizontally(self.image) }
@@ -241,6 +336,208 @@ impl Image {
                                    sfBool::from_bool(apply_alpha))
         }
     }
+
+    /// Return a copy of the part of the image inside `rect`.
+    ///
+    /// The rectangle is clipped to the image, the copy is empty if they don't intersect.
+    pub fn crop(&self, rect: &IntRect) -> Image {
+        let rect = self.clip(rect);
+        let width = self.size().x as usize;
+        let mut pixels = Vec::with_capacity((rect.width * rect.height) as usize);
+        for y in rect.top..rect.bottom() {
+            let start = y as usize * width + rect.left as usize;
+            pixels.extend_from_slice(&self.pixels()[start..start + rect.width as usize]);
+        }
+        from_colors(rect.width as u32, rect.height as u32, &pixels)
+    }
+
+    /// Return a copy of the image scaled to `width` x `height` pixels.
+    pub fn resized(&self, width: u32, height: u32, filter: ResizeFilter) -> Image {
+        let size = self.size();
+        let pixels = resize_pixels(self.pixels(), size.x, size.y, width, height, filter);
+        from_colors(width, height, &pixels)
+    }
+
+    /// Return a copy of the image rotated by 90 degrees clockwise.
+    pub fn rotated_clockwise(&self) -> Image {
+        let size = self.size();
+        from_colors(size.y, size.x, &rotate_pixels(self.pixels(), size.x, size.y, true))
+    }
+
+    /// Return a copy of the image rotated by 90 degrees counterclockwise.
+    pub fn rotated_counterclockwise(&self) -> Image {
+        let size = self.size();
+        from_colors(size.y, size.x, &rotate_pixels(self.pixels(), size.x, size.y, false))
+    }
+
+    /// Set the color of the pixels inside `rect`, clipped to the image.
+    pub fn fill_rect(&mut self, rect: &IntRect, color: &Color) {
+        let rect = self.clip(rect);
+        let width = self.size().x as usize;
+        let pixels = self.pixels_mut();
+        for y in rect.top..rect.bottom() {
+            let start = y as usize * width + rect.left as usize;
+            for pixel in &mut pixels[start..start + rect.width as usize] {
+                *pixel = *color;
+            }
+        }
+    }
+
+    /// Draw `source` over the image with its top-left corner at `(dest_x, dest_y)`,
+    /// mixing the colors with `blend_mode` as the GPU would.
+    ///
+    /// Unlike `copy_image`, the position may be negative and the source is clipped to the
+    /// image. Use `blend_mode::ALPHA` for usual alpha blending.
+    pub fn blend_image(&mut self,
+                       source: &Image,
+                       dest_x: i32,
+                       dest_y: i32,
+                       blend_mode: &BlendMode) {
+        let size = self.size();
+        let source_size = source.size();
+        let area = self.clip(&IntRect::new(dest_x,
+                                           dest_y,
+                                           source_size.x as i32,
+                                           source_size.y as i32));
+        let source_pixels = source.pixels();
+        let pixels = self.pixels_mut();
+        for y in area.top..area.bottom() {
+            for x in area.left..area.right() {
+                let source_index = (y - dest_y) as usize * source_size.x as usize +
+                                   (x - dest_x) as usize;
+                let pixel = &mut pixels[y as usize * size.x as usize + x as usize];
+                *pixel = blend_mode.blend(source_pixels[source_index], *pixel);
+            }
+        }
+    }
+
+    /// Replace each pixel with the result of `f`, called with its coordinates and color.
+    ///
+    /// # Usage example
+    ///
+    /// ```no_run
+    /// use sfml::graphics::{Color, Image};
+    ///
+    /// let mut image = Image::from_file("photo.png").unwrap();
+    /// // Grayscale
+    /// image.map_pixels(|_, _, c| {
+    ///     let gray = ((c.r as u32 + c.g as u32 + c.b as u32) / 3) as u8;
+    ///     Color::rgba(gray, gray, gray, c.a)
+    /// });
+    /// ```
+    pub fn map_pixels<F: FnMut(u32, u32, Color) -> Color>(&mut self, mut f: F) {
+        let width = self.size().x;
+        for (i, pixel) in self.pixels_mut().iter_mut().enumerate() {
+            let i = i as u32;
+            *pixel = f(i % width, i / width, *pixel);
+        }
+    }
+
+    /// Return the part of `rect` inside the image, empty if they don't intersect.
+    fn clip(&self, rect: &IntRect) -> IntRect {
+        let size = self.size();
+        let bounds = IntRect::new(0, 0, size.x as i32, size.y as i32);
+        rect.intersection(&bounds).unwrap_or_else(|| IntRect::new(0, 0, 0, 0))
+    }
+}
+
+/// The filter used to resize an image, see `Image::resized`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ResizeFilter {
+    /// Each pixel takes the color of the nearest source pixel, which keeps hard edges.
+    /// Best for pixel art.
+    Nearest,
+    /// Each pixel mixes the four nearest source pixels, which is smoother.
+    /// Colors are mixed with their alpha, so transparent pixels don't darken the edges.
+    Bilinear,
+}
+
+/// Create an image from its pixels.
+fn from_colors(width: u32, height: u32, pixels: &[Color]) -> Image {
+    let data = unsafe { slice::from_raw_parts(pixels.as_ptr() as *const u8, pixels.len() * 4) };
+    Image::create_from_pixels(width, height, data).expect("Not enough memory to create the Image")
+}
+
+fn resize_pixels(pixels: &[Color],
+                 width: u32,
+                 height: u32,
+                 new_width: u32,
+                 new_height: u32,
+                 filter: ResizeFilter)
+                 -> Vec<Color> {
+    let mut resized = Vec::with_capacity((new_width * new_height) as usize);
+    if width == 0 || height == 0 {
+        resized.resize((new_width * new_height) as usize, Color::transparent());
+        return resized;
+    }
+    let (scale_x, scale_y) = (width as f32 / new_width as f32, height as f32 / new_height as f32);
+    let at = |x: u32, y: u32| pixels[(y * width + x) as usize];
+    for y in 0..new_height {
+        // Position of the center of the pixel in the source, in pixels
+        let source_y = (y as f32 + 0.5) * scale_y;
+        for x in 0..new_width {
+            let source_x = (x as f32 + 0.5) * scale_x;
+            let color = match filter {
+                ResizeFilter::Nearest => {
+                    at((source_x as u32).min(width - 1), (source_y as u32).min(height - 1))
+                }
+                ResizeFilter::Bilinear => {
+                    let (x0, x1, tx) = neighbours(source_x, width);
+                    let (y0, y1, ty) = neighbours(source_y, height);
+                    mix(&[(at(x0, y0), (1. - tx) * (1. - ty)),
+                          (at(x1, y0), tx * (1. - ty)),
+                          (at(x0, y1), (1. - tx) * ty),
+                          (at(x1, y1), tx * ty)])
+                }
+            };
+            resized.push(color);
+        }
+    }
+    resized
+}
+
+/// Return the two pixels around the position `center` along an axis of length `len`,
+/// and the weight of the second one.
+fn neighbours(center: f32, len: u32) -> (u32, u32, f32) {
+    let position = (center - 0.5).max(0.).min((len - 1) as f32);
+    let first = position.floor();
+    (first as u32, (first as u32 + 1).min(len - 1), position - first)
+}
+
+/// Return the weighted average of colors, weighting the RGB components by their alpha.
+fn mix(colors: &[(Color, f32)]) -> Color {
+    let mut sum = [0f32; 4];
+    for &(color, weight) in colors {
+        let alpha = color.a as f32 * weight;
+        sum[0] += color.r as f32 * alpha;
+        sum[1] += color.g as f32 * alpha;
+        sum[2] += color.b as f32 * alpha;
+        sum[3] += alpha;
+    }
+    if sum[3] == 0. {
+        return Color::transparent();
+    }
+    let channel = |value: f32| (value / sum[3]).round().min(255.) as u8;
+    Color::rgba(channel(sum[0]),
+                channel(sum[1]),
+                channel(sum[2]),
+                sum[3].round().min(255.) as u8)
+}
+
+fn rotate_pixels(pixels: &[Color], width: u32, height: u32, clockwise: bool) -> Vec<Color> {
+    let mut rotated = Vec::with_capacity(pixels.len());
+    // The rotated image is `height` pixels wide and `width` pixels high
+    for y in 0..width {
+        for x in 0..height {
+            let (source_x, source_y) = if clockwise {
+                (y, height - 1 - x)
+            } else {
+                (width - 1 - y, x)
+            };
+            rotated.push(pixels[(source_y * width + source_x) as usize]);
+        }
+    }
+    rotated
 }
 
 impl Clone for Image {
@@ -274,3 +571,27 @@ impl Drop for Image {
         unsafe { ffi::sfImage_destroy(self.image) }
     }
 }
+
+#[test]
+fn resize_and_rotate() {
+    let (red, blue) = (Color::red(), Color::blue());
+    let pixels = [red, blue, Color::transparent(), Color::green(), red, blue];
+    // 3x2 image:
+    // red         blue   transparent
+    // green       red    blue
+    assert_eq!(rotate_pixels(&pixels, 3, 2, true),
+               [Color::green(), red, red, blue, blue, Color::transparent()]);
+    assert_eq!(rotate_pixels(&pixels, 3, 2, false),
+               [Color::transparent(), blue, blue, red, red, Color::green()]);
+
+    // The single row samples the middle of the image, on the edge of the second row
+    let nearest = resize_pixels(&pixels, 3, 2, 6, 1, ResizeFilter::Nearest);
+    assert_eq!(nearest, [Color::green(), Color::green(), red, red, blue, blue]);
+    let bilinear = resize_pixels(&[red, blue], 2, 1, 4, 1, ResizeFilter::Bilinear);
+    assert_eq!(bilinear,
+               [red, Color::rgb(191, 0, 64), Color::rgb(64, 0, 191), blue]);
+    // Transparent pixels don't bleed their color
+    let faded = resize_pixels(&[red, Color::transparent()], 2, 1, 4, 1, ResizeFilter::Bilinear);
+    assert_eq!(faded[1], Color::rgba(255, 0, 0, 191));
+    assert_eq!(mix(&[]), Color::transparent());
+}
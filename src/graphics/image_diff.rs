@@ -0,0 +1,196 @@
+use graphics::{Image, IntRect};
+use std::cmp;
+use std::fmt;
+use system::Vector2u;
+
+/// Color of the differing pixels in the diff image.
+const HIGHLIGHT: [u8; 4] = [255, 0, 0, 255];
+
+/// The result of comparing two images with `image_diff`.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{image_diff, Color, Image, RenderTarget, RenderTexture};
+///
+/// let mut target = RenderTexture::new(64, 64, false).unwrap();
+/// target.clear(&Color::blue());
+/// target.display();
+///
+/// let expected = Image::from_file("tests/golden/blue.png").unwrap();
+/// let actual = target.texture().copy_to_image().unwrap();
+/// let diff = image_diff(&expected, &actual, 2);
+/// if !diff.is_match() {
+///     diff.diff_image().save_to_file("blue.diff.png").unwrap();
+///     panic!("rendering differs from the golden image: {}", diff);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageDiff {
+    size: Vector2u,
+    differing_pixels: u32,
+    bounds: Option<IntRect>,
+    max_difference: u8,
+    highlighted: Vec<u8>,
+}
+
+/// Compare `actual` with `expected`, pixel by pixel.
+///
+/// Two pixels match if none of their channels (alpha included) differ by more than
+/// `tolerance`. If the images don't have the same size, the comparison covers the size
+/// of the largest one, and pixels that only exist in one of them differ.
+pub fn image_diff(expected: &Image, actual: &Image, tolerance: u8) -> ImageDiff {
+    ImageDiff::from_pixels(expected.pixel_data(),
+                           expected.size(),
+                           actual.pixel_data(),
+                           actual.size(),
+                           tolerance)
+}
+
+impl ImageDiff {
+    /// Compare two buffers of RGBA pixels, see `image_diff`.
+    fn from_pixels(expected: &[u8],
+                   expected_size: Vector2u,
+                   actual: &[u8],
+                   actual_size: Vector2u,
+                   tolerance: u8)
+                   -> ImageDiff {
+        let size = Vector2u::new(cmp::max(expected_size.x, actual_size.x),
+                                 cmp::max(expected_size.y, actual_size.y));
+        let mut diff = ImageDiff {
+            size,
+            differing_pixels: 0,
+            bounds: None,
+            max_difference: 0,
+            highlighted: Vec::with_capacity((size.x * size.y) as usize * 4),
+        };
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let difference = match (pixel(expected, expected_size, x, y),
+                                        pixel(actual, actual_size, x, y)) {
+                    (Some(e), Some(a)) => {
+                        let difference = e.iter()
+                            .zip(&a)
+                            .map(|(&e, &a)| (i16::from(e) - i16::from(a)).unsigned_abs() as u8)
+                            .max()
+                            .unwrap_or(0);
+                        if difference <= tolerance {
+                            diff.highlighted.extend_from_slice(&faded(e));
+                            continue;
+                        }
+                        difference
+                    }
+                    _ => 255,
+                };
+                diff.differing_pixels += 1;
+                diff.max_difference = cmp::max(diff.max_difference, difference);
+                let point = IntRect::new(x as i32, y as i32, 1, 1);
+                diff.bounds = Some(diff.bounds.map_or(point, |b| b.union(&point)));
+                diff.highlighted.extend_from_slice(&HIGHLIGHT);
+            }
+        }
+        diff
+    }
+
+    /// Tell whether all the pixels matched.
+    pub fn is_match(&self) -> bool {
+        self.differing_pixels == 0
+    }
+
+    /// Return the number of pixels that differ by more than the tolerance.
+    pub fn differing_pixels(&self) -> u32 {
+        self.differing_pixels
+    }
+
+    /// Return the smallest rectangle containing all the differing pixels,
+    /// or `None` if the images match.
+    pub fn bounds(&self) -> Option<IntRect> {
+        self.bounds
+    }
+
+    /// Return the largest channel difference among the differing pixels, 0 if the images
+    /// match.
+    pub fn max_difference(&self) -> u8 {
+        self.max_difference
+    }
+
+    /// Return the size of the compared area, the size of the largest image.
+    pub fn size(&self) -> Vector2u {
+        self.size
+    }
+
+    /// Return an image of the comparison, of the size of the compared area.
+    ///
+    /// Differing pixels are opaque red, matching ones are a faded, grayscale version of the
+    /// expected image so that the differences stand out while staying easy to locate.
+    pub fn diff_image(&self) -> Image {
+        Image::create_from_pixels(self.size.x, self.size.y, &self.highlighted)
+            .expect("Not enough memory to create the Image")
+    }
+}
+
+impl fmt::Display for ImageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.bounds {
+            None => write!(f, "images match ({}x{})", self.size.x, self.size.y),
+            Some(bounds) => {
+                write!(f,
+                       "{} of {}x{} pixels differ within ({}, {}) {}x{}, \
+                        max channel difference {}",
+                       self.differing_pixels,
+                       self.size.x,
+                       self.size.y,
+                       bounds.left,
+                       bounds.top,
+                       bounds.width,
+                       bounds.height,
+                       self.max_difference)
+            }
+        }
+    }
+}
+
+/// Return the pixel at `(x, y)`, or `None` if it is out of the image.
+fn pixel(data: &[u8], size: Vector2u, x: u32, y: u32) -> Option<[u8; 4]> {
+    if x < size.x && y < size.y {
+        let index = (y * size.x + x) as usize * 4;
+        Some([data[index], data[index + 1], data[index + 2], data[index + 3]])
+    } else {
+        None
+    }
+}
+
+/// Return a light gray pixel of the luminance of `pixel`, as if drawn over white.
+fn faded(pixel: [u8; 4]) -> [u8; 4] {
+    let [r, g, b, a] = pixel;
+    let luminance = (u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000;
+    let over_white = (luminance * u32::from(a) + 255 * (255 - u32::from(a))) / 255;
+    // Keep a quarter of the contrast
+    let gray = (192 + over_white / 4) as u8;
+    [gray, gray, gray, 255]
+}
+
+#[test]
+fn compare_pixels() {
+    let expected = [0, 0, 0, 255, 10, 20, 30, 255, 255, 255, 255, 0, 9, 9, 9, 9];
+    let mut actual = expected;
+    actual[4] = 13;
+    actual[11] = 2;
+    let size = Vector2u::new(2, 2);
+    let diff = ImageDiff::from_pixels(&expected, size, &actual, size, 2);
+    assert!(!diff.is_match());
+    assert_eq!(diff.differing_pixels(), 1);
+    assert_eq!(diff.bounds(), Some(IntRect::new(1, 0, 1, 1)));
+    assert_eq!(diff.max_difference(), 3);
+    assert_eq!(&diff.highlighted[4..8], &HIGHLIGHT);
+    assert_eq!(&diff.highlighted[0..4], &[192, 192, 192, 255]);
+    assert_eq!(&diff.highlighted[8..12], &[255, 255, 255, 255]);
+    assert!(ImageDiff::from_pixels(&expected, size, &actual, size, 3).is_match());
+
+    let column: Vec<u8> = expected[0..4].iter().chain(&expected[8..12]).cloned().collect();
+    let diff = ImageDiff::from_pixels(&expected, size, &column, Vector2u::new(1, 2), 0);
+    assert_eq!(diff.differing_pixels(), 2);
+    assert_eq!(diff.bounds(), Some(IntRect::new(1, 0, 1, 2)));
+    assert_eq!(diff.to_string(),
+               "2 of 2x2 pixels differ within (1, 0) 1x2, max channel difference 255");
+}
@@ -0,0 +1,670 @@
+use error::{self, Error, ErrorKind};
+use std::cmp;
+
+/// A file format images can be encoded to, see `Image::save_to_memory`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ImageFormat {
+    /// PNG: lossless, with transparency.
+    ///
+    /// The encoder favours simplicity over size, files are somewhat larger than those of
+    /// dedicated tools.
+    Png,
+    /// Windows bitmap: uncompressed, 32 bits per pixel with transparency.
+    Bmp,
+    /// Targa: run-length encoded, with transparency.
+    Tga,
+    /// Baseline JPEG: lossy, without transparency (the alpha channel is ignored).
+    Jpeg {
+        /// From 1 (smallest files) to 100 (best quality), clamped. 90 is a good default.
+        quality: u8,
+    },
+}
+
+/// Encode RGBA pixels, row by row.
+pub(crate) fn encode(pixels: &[u8],
+                     width: u32,
+                     height: u32,
+                     format: ImageFormat)
+                     -> error::Result<Vec<u8>> {
+    if width == 0 || height == 0 {
+        return Err(Error::new(ErrorKind::EncodeFailed).with_message("the image is empty"));
+    }
+    let limit = match format {
+        ImageFormat::Png | ImageFormat::Bmp => i32::MAX as u32,
+        ImageFormat::Tga | ImageFormat::Jpeg { .. } => u16::MAX as u32,
+    };
+    if width > limit || height > limit {
+        let message = format!("{:?} images can't be larger than {} pixels", format, limit);
+        return Err(Error::new(ErrorKind::EncodeFailed).with_message(message));
+    }
+    let mut out = Vec::new();
+    match format {
+        ImageFormat::Png => encode_png(&mut out, pixels, width, height),
+        ImageFormat::Bmp => encode_bmp(&mut out, pixels, width, height)?,
+        ImageFormat::Tga => encode_tga(&mut out, pixels, width, height),
+        ImageFormat::Jpeg { quality } => encode_jpeg(&mut out, pixels, width, height, quality),
+    }
+    Ok(out)
+}
+
+// PNG
+
+fn encode_png(out: &mut Vec<u8>, pixels: &[u8], width: u32, height: u32) {
+    out.extend_from_slice(b"\x89PNG\r\n\x1a\n");
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, deflate, adaptive filtering, not interlaced
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+    png_chunk(out, b"IHDR", &header);
+
+    let stride = width as usize * 4;
+    let mut filtered = Vec::with_capacity((stride + 1) * height as usize);
+    let mut previous = vec![0; stride];
+    for row in pixels.chunks(stride) {
+        filter_row(&mut filtered, row, &previous);
+        previous.copy_from_slice(row);
+    }
+    png_chunk(out, b"IDAT", &zlib(&filtered));
+    png_chunk(out, b"IEND", &[]);
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&crc32(&[kind, data]).to_be_bytes());
+}
+
+/// Append the filter type and the filtered row, using the filter that gives the smallest
+/// sum of absolute differences, the heuristic recommended by the PNG specification.
+fn filter_row(out: &mut Vec<u8>, row: &[u8], previous: &[u8]) {
+    let mut best = vec![0; row.len()];
+    let mut candidate = vec![0; row.len()];
+    let (mut best_filter, mut best_score) = (0, u64::MAX);
+    for filter in 0..5 {
+        for i in 0..row.len() {
+            let left = if i >= 4 { row[i - 4] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= 4 { previous[i - 4] } else { 0 };
+            let prediction = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+                _ => paeth(left, up, up_left),
+            };
+            candidate[i] = row[i].wrapping_sub(prediction);
+        }
+        let score: u64 = candidate.iter().map(|&v| u64::from((v as i8).unsigned_abs())).sum();
+        if score < best_score {
+            best_filter = filter;
+            best_score = score;
+            ::std::mem::swap(&mut best, &mut candidate);
+        }
+    }
+    out.push(best_filter);
+    out.extend_from_slice(&best);
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(up) - i16::from(up_left);
+    let distance = |v: u8| (estimate - i16::from(v)).abs();
+    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+        left
+    } else if distance(up) <= distance(up_left) {
+        up
+    } else {
+        up_left
+    }
+}
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut table = [0u32; 256];
+    for (n, entry) in table.iter_mut().enumerate() {
+        let mut c = n as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+        }
+        *entry = c;
+    }
+    let mut crc = 0xFFFF_FFFF;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        crc = table[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xFFFF_FFFF
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 bytes is the most that can be summed before `b` overflows
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+// Deflate, with LZ77 matching and the fixed Huffman codes
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// How many previous positions with the same hash are tried when looking for a match.
+const MAX_CHAIN: usize = 64;
+const NO_POSITION: usize = usize::MAX;
+
+const LENGTH_BASES: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35,
+                                 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA_BITS: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3,
+                                     3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASES: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+                                   257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+                                   8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7,
+                                       8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// Compress `data` in the zlib format.
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut writer = DeflateWriter {
+        out: vec![0x78, 0x9C],
+        bits: 0,
+        bit_count: 0,
+    };
+    // A single final block, with the fixed Huffman codes
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    // Chains of the previous positions with the same hash, by hash and by position
+    let mut head = vec![NO_POSITION; 0x8000];
+    let mut previous = vec![NO_POSITION; WINDOW_SIZE];
+
+    let mut i = 0;
+    while i < data.len() {
+        let (mut best_length, mut best_distance) = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let max_length = cmp::min(MAX_MATCH, data.len() - i);
+            let mut candidate = head[hash(data, i)];
+            let mut chain = 0;
+            while candidate != NO_POSITION && i - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+                let length = data[candidate..]
+                    .iter()
+                    .zip(&data[i..i + max_length])
+                    .take_while(|&(a, b)| a == b)
+                    .count();
+                if length > best_length {
+                    best_length = length;
+                    best_distance = i - candidate;
+                    if length == max_length {
+                        break;
+                    }
+                }
+                candidate = previous[candidate % WINDOW_SIZE];
+                chain += 1;
+            }
+        }
+        if best_length >= MIN_MATCH {
+            writer.write_match(best_length as u16, best_distance as u16);
+            for position in i..i + best_length {
+                insert(&mut head, &mut previous, data, position);
+            }
+            i += best_length;
+        } else {
+            writer.write_literal_length(u16::from(data[i]));
+            insert(&mut head, &mut previous, data, i);
+            i += 1;
+        }
+    }
+    writer.write_literal_length(256);
+    if writer.bit_count > 0 {
+        writer.out.push(writer.bits as u8);
+    }
+
+    let mut out = writer.out;
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn hash(data: &[u8], i: usize) -> usize {
+    ((usize::from(data[i]) << 10) ^ (usize::from(data[i + 1]) << 5) ^ usize::from(data[i + 2])) &
+    0x7FFF
+}
+
+fn insert(head: &mut [usize], previous: &mut [usize], data: &[u8], i: usize) {
+    if i + MIN_MATCH <= data.len() {
+        let h = hash(data, i);
+        previous[i % WINDOW_SIZE] = head[h];
+        head[h] = i;
+    }
+}
+
+struct DeflateWriter {
+    out: Vec<u8>,
+    bits: u32,
+    bit_count: u32,
+}
+
+impl DeflateWriter {
+    /// Write the `count` low bits of `value`, least significant bit first.
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.bits |= value << self.bit_count;
+        self.bit_count += count;
+        while self.bit_count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    /// Write a Huffman code, which are packed most significant bit first.
+    fn write_code(&mut self, code: u16, length: u32) {
+        let reversed = u32::from(code.reverse_bits() >> (16 - length));
+        self.write_bits(reversed, length);
+    }
+
+    fn write_literal_length(&mut self, symbol: u16) {
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn write_match(&mut self, length: u16, distance: u16) {
+        let index = LENGTH_BASES.iter().rposition(|&base| base <= length).unwrap();
+        self.write_literal_length(257 + index as u16);
+        self.write_bits(u32::from(length - LENGTH_BASES[index]),
+                        u32::from(LENGTH_EXTRA_BITS[index]));
+        let index = DISTANCE_BASES.iter().rposition(|&base| base <= distance).unwrap();
+        self.write_code(index as u16, 5);
+        self.write_bits(u32::from(distance - DISTANCE_BASES[index]),
+                        u32::from(DISTANCE_EXTRA_BITS[index]));
+    }
+}
+
+// BMP
+
+fn encode_bmp(out: &mut Vec<u8>,
+              pixels: &[u8],
+              width: u32,
+              height: u32)
+              -> error::Result<()> {
+    const HEADERS_SIZE: u32 = 14 + 108;
+    // The file and data sizes are stored on 32 bits
+    let data_size = width.checked_mul(height).and_then(|count| count.checked_mul(4));
+    let file_size = data_size.and_then(|size| size.checked_add(HEADERS_SIZE));
+    let (data_size, file_size) = match (data_size, file_size) {
+        (Some(data_size), Some(file_size)) => (data_size, file_size),
+        _ => {
+            return Err(Error::new(ErrorKind::EncodeFailed)
+                           .with_message("Bmp images can't be larger than 4 GiB"))
+        }
+    };
+    out.extend_from_slice(b"BM");
+    for &value in &[file_size, 0, HEADERS_SIZE] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    // BITMAPV4HEADER, so that readers know about the alpha channel
+    out.extend_from_slice(&108u32.to_le_bytes());
+    out.extend_from_slice(&width.to_le_bytes());
+    // A positive height means that rows are stored bottom-up
+    out.extend_from_slice(&height.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&32u16.to_le_bytes());
+    // BI_BITFIELDS, the image size, 72 DPI, no palette, then the channel masks
+    for &value in &[3, data_size, 2835, 2835, 0, 0, 0x00FF_0000, 0x0000_FF00, 0x0000_00FF,
+                    0xFF00_0000] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    // sRGB color space, whose endpoints and gamma are unused
+    out.extend_from_slice(b"BGRs");
+    out.extend_from_slice(&[0; 48]);
+
+    for row in pixels.chunks(width as usize * 4).rev() {
+        for pixel in row.chunks(4) {
+            out.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+        }
+    }
+    Ok(())
+}
+
+// TGA
+
+fn encode_tga(out: &mut Vec<u8>, pixels: &[u8], width: u32, height: u32) {
+    // No ID nor palette, run-length encoded true-color
+    out.extend_from_slice(&[0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    out.extend_from_slice(&(width as u16).to_le_bytes());
+    out.extend_from_slice(&(height as u16).to_le_bytes());
+    // 32 bits per pixel, 8 of which are alpha, rows stored top-down
+    out.extend_from_slice(&[32, 0x28]);
+
+    let bgra = |pixel: &[u8]| [pixel[2], pixel[1], pixel[0], pixel[3]];
+    // Packets don't cross rows, as recommended by the specification
+    for row in pixels.chunks(width as usize * 4) {
+        let row: Vec<&[u8]> = row.chunks(4).collect();
+        let run_length = |start: usize| {
+            row[start..].iter().take(128).take_while(|&&p| p == row[start]).count()
+        };
+        let mut i = 0;
+        while i < row.len() {
+            let run = run_length(i);
+            if run > 1 {
+                out.push(0x80 | (run - 1) as u8);
+                out.extend_from_slice(&bgra(row[i]));
+                i += run;
+            } else {
+                let mut end = i + 1;
+                while end < row.len() && end - i < 128 && run_length(end) == 1 {
+                    end += 1;
+                }
+                out.push((end - i - 1) as u8);
+                for pixel in &row[i..end] {
+                    out.extend_from_slice(&bgra(pixel));
+                }
+                i = end;
+            }
+        }
+    }
+    // TGA 2.0 footer, without extension nor developer areas
+    out.extend_from_slice(&[0; 8]);
+    out.extend_from_slice(b"TRUEVISION-XFILE.\0");
+}
+
+// JPEG
+
+/// Natural index of the coefficients in zig-zag order.
+const ZIGZAG: [usize; 64] = [0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19,
+                             26, 33, 40, 48, 41, 34, 27, 20, 13, 6, 7, 14, 21, 28, 35, 42, 49,
+                             56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59, 52,
+                             45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63];
+
+/// The example quantization tables of the JPEG specification, for quality 50.
+const LUMINANCE_QUANTIZATION: [u16; 64] =
+    [16, 11, 10, 16, 24, 40, 51, 61, 12, 12, 14, 19, 26, 58, 60, 55, 14, 13, 16, 24, 40, 57,
+     69, 56, 14, 17, 22, 29, 51, 87, 80, 62, 18, 22, 37, 56, 68, 109, 103, 77, 24, 35, 55, 64,
+     81, 104, 113, 92, 49, 64, 78, 87, 103, 121, 120, 101, 72, 92, 95, 98, 112, 100, 103, 99];
+const CHROMINANCE_QUANTIZATION: [u16; 64] =
+    [17, 18, 24, 47, 99, 99, 99, 99, 18, 21, 26, 66, 99, 99, 99, 99, 24, 26, 56, 99, 99, 99,
+     99, 99, 47, 66, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99,
+     99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99];
+
+/// The example Huffman tables of the JPEG specification, as the number of codes of each
+/// length and the symbols ordered by code.
+const LUMINANCE_DC_COUNTS: [u8; 16] = [0, 1, 5, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+const CHROMINANCE_DC_COUNTS: [u8; 16] = [0, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0];
+const DC_SYMBOLS: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+const LUMINANCE_AC_COUNTS: [u8; 16] = [0, 2, 1, 3, 3, 2, 4, 3, 5, 5, 4, 4, 0, 0, 1, 0x7D];
+const LUMINANCE_AC_SYMBOLS: [u8; 162] =
+    [0x01, 0x02, 0x03, 0x00, 0x04, 0x11, 0x05, 0x12, 0x21, 0x31, 0x41, 0x06, 0x13, 0x51, 0x61,
+     0x07, 0x22, 0x71, 0x14, 0x32, 0x81, 0x91, 0xA1, 0x08, 0x23, 0x42, 0xB1, 0xC1, 0x15, 0x52,
+     0xD1, 0xF0, 0x24, 0x33, 0x62, 0x72, 0x82, 0x09, 0x0A, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x25,
+     0x26, 0x27, 0x28, 0x29, 0x2A, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x43, 0x44, 0x45,
+     0x46, 0x47, 0x48, 0x49, 0x4A, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A, 0x63, 0x64,
+     0x65, 0x66, 0x67, 0x68, 0x69, 0x6A, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0x83,
+     0x84, 0x85, 0x86, 0x87, 0x88, 0x89, 0x8A, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99,
+     0x9A, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xB2, 0xB3, 0xB4, 0xB5, 0xB6,
+     0xB7, 0xB8, 0xB9, 0xBA, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xD2, 0xD3,
+     0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0xDA, 0xE1, 0xE2, 0xE3, 0xE4, 0xE5, 0xE6, 0xE7, 0xE8,
+     0xE9, 0xEA, 0xF1, 0xF2, 0xF3, 0xF4, 0xF5, 0xF6, 0xF7, 0xF8, 0xF9, 0xFA];
+const CHROMINANCE_AC_COUNTS: [u8; 16] = [0, 2, 1, 2, 4, 4, 3, 4, 7, 5, 4, 4, 0, 1, 2, 0x77];
+const CHROMINANCE_AC_SYMBOLS: [u8; 162] =
+    [0x00, 0x01, 0x02, 0x03, 0x11, 0x04, 0x05, 0x21, 0x31, 0x06, 0x12, 0x41, 0x51, 0x07, 0x61,
+     0x71, 0x13, 0x22, 0x32, 0x81, 0x08, 0x14, 0x42, 0x91, 0xA1, 0xB1, 0xC1, 0x09, 0x23, 0x33,
+     0x52, 0xF0, 0x15, 0x62, 0x72, 0xD1, 0x0A, 0x16, 0x24, 0x34, 0xE1, 0x25, 0xF1, 0x17, 0x18,
+     0x19, 0x1A, 0x26, 0x27, 0x28, 0x29, 0x2A, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x43, 0x44,
+     0x45, 0x46, 0x47, 0x48, 0x49, 0x4A, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A, 0x63,
+     0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0x6A, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A,
+     0x82, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89, 0x8A, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97,
+     0x98, 0x99, 0x9A, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xB2, 0xB3, 0xB4,
+     0xB5, 0xB6, 0xB7, 0xB8, 0xB9, 0xBA, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA,
+     0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0xDA, 0xE2, 0xE3, 0xE4, 0xE5, 0xE6, 0xE7,
+     0xE8, 0xE9, 0xEA, 0xF2, 0xF3, 0xF4, 0xF5, 0xF6, 0xF7, 0xF8, 0xF9, 0xFA];
+
+/// A Huffman table, as the `(code, length)` of each symbol.
+struct HuffmanTable([(u16, u8); 256]);
+
+impl HuffmanTable {
+    fn new(counts: &[u8; 16], symbols: &[u8]) -> HuffmanTable {
+        let mut codes = [(0, 0); 256];
+        let mut symbols = symbols.iter();
+        let mut code = 0;
+        for (length, &count) in (1..).zip(counts) {
+            for _ in 0..count {
+                codes[usize::from(*symbols.next().unwrap())] = (code, length);
+                code += 1;
+            }
+            code <<= 1;
+        }
+        HuffmanTable(codes)
+    }
+}
+
+/// Writes the entropy-coded data, most significant bit first.
+struct JpegWriter<'a> {
+    out: &'a mut Vec<u8>,
+    bits: u32,
+    bit_count: u32,
+}
+
+impl<'a> JpegWriter<'a> {
+    fn write_bits(&mut self, value: u16, count: u8) {
+        self.bits = (self.bits << count) | (u32::from(value) & ((1 << count) - 1));
+        self.bit_count += u32::from(count);
+        while self.bit_count >= 8 {
+            let byte = (self.bits >> (self.bit_count - 8)) as u8;
+            self.out.push(byte);
+            // Bytes that look like markers are escaped
+            if byte == 0xFF {
+                self.out.push(0);
+            }
+            self.bit_count -= 8;
+        }
+    }
+
+    fn write_symbol(&mut self, table: &HuffmanTable, symbol: u8) {
+        let (code, length) = table.0[usize::from(symbol)];
+        self.write_bits(code, length);
+    }
+
+    /// Write a coefficient (or the difference of two DC coefficients) as its number of bits
+    /// followed by its value, negative values being written minus one.
+    fn write_value(&mut self, table: &HuffmanTable, zero_run: u8, value: i32) {
+        let size = 32 - value.abs().leading_zeros();
+        self.write_symbol(table, (zero_run << 4) | size as u8);
+        let bits = if value < 0 { value - 1 } else { value };
+        self.write_bits(bits as u16, size as u8);
+    }
+
+    /// Pad the last byte with 1 bits.
+    fn flush(&mut self) {
+        if self.bit_count > 0 {
+            let padding = 8 - self.bit_count as u8;
+            self.write_bits(0xFF, padding);
+        }
+    }
+}
+
+fn encode_jpeg(out: &mut Vec<u8>, pixels: &[u8], width: u32, height: u32, quality: u8) {
+    let quality = u32::from(quality.clamp(1, 100));
+    // Scaling of the example tables, as done by the IJG library
+    let scale = if quality < 50 { 5000 / quality } else { 200 - quality * 2 };
+    let quantization = |base: &[u16; 64]| {
+        let mut table = [0u16; 64];
+        for (value, &base) in table.iter_mut().zip(base.iter()) {
+            *value = ((u32::from(base) * scale + 50) / 100).clamp(1, 255) as u16;
+        }
+        table
+    };
+    let quantization = [quantization(&LUMINANCE_QUANTIZATION),
+                        quantization(&CHROMINANCE_QUANTIZATION)];
+
+    let segment = |out: &mut Vec<u8>, marker: u8, data: &[u8]| {
+        out.extend_from_slice(&[0xFF, marker]);
+        out.extend_from_slice(&(data.len() as u16 + 2).to_be_bytes());
+        out.extend_from_slice(data);
+    };
+    // Start of image, then the JFIF header: version 1.1, no density, no thumbnail
+    out.extend_from_slice(&[0xFF, 0xD8]);
+    segment(out, 0xE0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+    for (id, table) in quantization.iter().enumerate() {
+        let mut data = vec![id as u8];
+        data.extend(ZIGZAG.iter().map(|&i| table[i] as u8));
+        segment(out, 0xDB, &data);
+    }
+    // Baseline frame, with 3 components that aren't subsampled
+    let mut frame = vec![8];
+    frame.extend_from_slice(&(height as u16).to_be_bytes());
+    frame.extend_from_slice(&(width as u16).to_be_bytes());
+    frame.extend_from_slice(&[3, 1, 0x11, 0, 2, 0x11, 1, 3, 0x11, 1]);
+    segment(out, 0xC0, &frame);
+    let huffman = [(0x00, &LUMINANCE_DC_COUNTS, &DC_SYMBOLS[..]),
+                   (0x10, &LUMINANCE_AC_COUNTS, &LUMINANCE_AC_SYMBOLS[..]),
+                   (0x01, &CHROMINANCE_DC_COUNTS, &DC_SYMBOLS[..]),
+                   (0x11, &CHROMINANCE_AC_COUNTS, &CHROMINANCE_AC_SYMBOLS[..])];
+    for &(class_and_id, counts, symbols) in &huffman {
+        let mut data = vec![class_and_id];
+        data.extend_from_slice(counts);
+        data.extend_from_slice(symbols);
+        segment(out, 0xC4, &data);
+    }
+    segment(out, 0xDA, &[3, 1, 0x00, 2, 0x11, 3, 0x11, 0, 63, 0]);
+
+    let dc_tables = [HuffmanTable::new(&LUMINANCE_DC_COUNTS, &DC_SYMBOLS),
+                     HuffmanTable::new(&CHROMINANCE_DC_COUNTS, &DC_SYMBOLS)];
+    let ac_tables = [HuffmanTable::new(&LUMINANCE_AC_COUNTS, &LUMINANCE_AC_SYMBOLS),
+                     HuffmanTable::new(&CHROMINANCE_AC_COUNTS, &CHROMINANCE_AC_SYMBOLS)];
+    let mut cosines = [[0f32; 8]; 8];
+    for (x, row) in cosines.iter_mut().enumerate() {
+        for (u, cosine) in row.iter_mut().enumerate() {
+            *cosine = ((2 * x + 1) as f32 * u as f32 * ::std::f32::consts::PI / 16.).cos();
+        }
+    }
+
+    let mut writer = JpegWriter {
+        out,
+        bits: 0,
+        bit_count: 0,
+    };
+    let mut previous_dc = [0i32; 3];
+    for block_y in 0..height.div_ceil(8) {
+        for block_x in 0..width.div_ceil(8) {
+            // Level-shifted YCbCr samples, repeating the last row and column of the image
+            let (mut luma, mut blue, mut red) = ([0f32; 64], [0f32; 64], [0f32; 64]);
+            let samples = luma.iter_mut().zip(blue.iter_mut()).zip(red.iter_mut());
+            for (i, ((luma, blue), red)) in samples.enumerate() {
+                let x = cmp::min(block_x * 8 + i as u32 % 8, width - 1);
+                let y = cmp::min(block_y * 8 + i as u32 / 8, height - 1);
+                let index = (y * width + x) as usize * 4;
+                let (r, g, b) = (f32::from(pixels[index]),
+                                 f32::from(pixels[index + 1]),
+                                 f32::from(pixels[index + 2]));
+                *luma = 0.299 * r + 0.587 * g + 0.114 * b - 128.;
+                *blue = -0.168_736 * r - 0.331_264 * g + 0.5 * b;
+                *red = 0.5 * r - 0.418_688 * g - 0.081_312 * b;
+            }
+            let blocks = [luma, blue, red];
+            for (component, block) in blocks.iter().enumerate() {
+                let table = cmp::min(component, 1);
+                let coefficients = dct(block, &cosines);
+                let quantized: Vec<i32> = ZIGZAG.iter()
+                    .map(|&i| {
+                        let value = coefficients[i] / f32::from(quantization[table][i]);
+                        // Baseline AC coefficients are limited to 10 bits
+                        (value.round() as i32).clamp(-1023, 1023)
+                    })
+                    .collect();
+
+                let dc = quantized[0];
+                writer.write_value(&dc_tables[table], 0, dc - previous_dc[component]);
+                previous_dc[component] = dc;
+                let mut zero_run = 0;
+                for &coefficient in &quantized[1..] {
+                    if coefficient == 0 {
+                        zero_run += 1;
+                        continue;
+                    }
+                    while zero_run >= 16 {
+                        writer.write_symbol(&ac_tables[table], 0xF0);
+                        zero_run -= 16;
+                    }
+                    writer.write_value(&ac_tables[table], zero_run, coefficient);
+                    zero_run = 0;
+                }
+                if zero_run > 0 {
+                    // End of block
+                    writer.write_symbol(&ac_tables[table], 0x00);
+                }
+            }
+        }
+    }
+    writer.flush();
+    writer.out.extend_from_slice(&[0xFF, 0xD9]);
+}
+
+/// Compute the 2D discrete cosine transform of a block of samples, row by row.
+fn dct(block: &[f32; 64], cosines: &[[f32; 8]; 8]) -> [f32; 64] {
+    let scale = |u: usize| if u == 0 { ::std::f32::consts::FRAC_1_SQRT_2 } else { 1. };
+    let mut rows = [0f32; 64];
+    for y in 0..8 {
+        for u in 0..8 {
+            let sum: f32 = (0..8).map(|x| block[y * 8 + x] * cosines[x][u]).sum();
+            rows[y * 8 + u] = sum * scale(u) / 2.;
+        }
+    }
+    let mut coefficients = [0f32; 64];
+    for v in 0..8 {
+        for u in 0..8 {
+            let sum: f32 = (0..8).map(|y| rows[y * 8 + u] * cosines[y][v]).sum();
+            coefficients[v * 8 + u] = sum * scale(v) / 2.;
+        }
+    }
+    coefficients
+}
+
+#[test]
+fn encode_formats() {
+    assert_eq!(crc32(&[b"IEND"]), 0xAE42_6082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    for &(counts, symbols) in &[(&LUMINANCE_AC_COUNTS, &LUMINANCE_AC_SYMBOLS),
+                                (&CHROMINANCE_AC_COUNTS, &CHROMINANCE_AC_SYMBOLS)] {
+        assert_eq!(counts.iter().map(|&c| usize::from(c)).sum::<usize>(), symbols.len());
+    }
+
+    // Fixed Huffman block: literal 'a' (0x91), a match of length 5 at distance 1, end
+    assert_eq!(zlib(b"aaaaaa"),
+               [0x78, 0x9C, 0x4B, 0x04, 0x03, 0x00, 0x07, 0xFB, 0x02, 0x47]);
+
+    let pixels = [255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 0, 0, 255, 128];
+    let tga = encode(&pixels, 4, 1, ImageFormat::Tga).unwrap();
+    assert_eq!(&tga[18..28], &[0x82, 0, 0, 255, 255, 0x00, 255, 0, 0, 128]);
+    let bmp = encode(&pixels, 2, 2, ImageFormat::Bmp).unwrap();
+    assert_eq!(bmp.len(), 122 + 16);
+    // Bottom-up BGRA
+    assert_eq!(&bmp[122..130], &[0, 0, 255, 255, 255, 0, 0, 128]);
+    // The expected encodings were checked against independent decoders
+    let png = encode(&pixels, 2, 2, ImageFormat::Png).unwrap();
+    let idat = [0x78, 0x9C, 0x63, 0xFC, 0xCF, 0xC0, 0x00, 0x44, 0x0C, 0x0C, 0x4C, 0x20, 0x82,
+                0x91, 0xE1, 0x7F, 0x23, 0x00, 0x21, 0x9A, 0x03, 0x83];
+    let mut expected = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut expected, b"IHDR", &[0, 0, 0, 2, 0, 0, 0, 2, 8, 6, 0, 0, 0]);
+    png_chunk(&mut expected, b"IDAT", &idat);
+    png_chunk(&mut expected, b"IEND", &[]);
+    assert_eq!(png, expected);
+    assert_eq!(crc32(&[&png]), 0xF0DD_A154);
+    // A solid image: the scan holds the DC of each channel, then an end of block
+    let solid: Vec<u8> = (0..64).flat_map(|_| vec![200, 100, 50, 255]).collect();
+    let jpeg = encode(&solid, 8, 8, ImageFormat::Jpeg { quality: 90 }).unwrap();
+    assert_eq!(&jpeg[jpeg.len() - 8..], [0xAB, 0x5F, 0x87, 0x9F, 0xD2, 0x07, 0xFF, 0xD9]);
+    assert_eq!((jpeg.len(), crc32(&[&jpeg])), (631, 0x9300_C28F));
+
+    let error = encode(&[], 0, 0, ImageFormat::Png).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::EncodeFailed);
+    // 40000 x 40000 x 4 bytes overflow the 32-bit sizes of the BMP headers
+    let error = encode(&[], 40000, 40000, ImageFormat::Bmp).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::EncodeFailed);
+}
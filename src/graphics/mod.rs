@@ -2,34 +2,46 @@
 
 extern crate csfml_graphics_sys;
 
+pub use self::animated_sprite::{AnimatedSprite, Animation, AnimationEvent, PlaybackMode};
 pub use self::blend_mode::BlendMode;
 pub use self::circle_shape::CircleShape;
-pub use self::color::Color;
+pub use self::color::{Color, ParseColorError};
 pub use self::convex_shape::{ConvexShape, ConvexShapePoints};
 pub use self::custom_shape::{CustomShape, CustomShapePoints};
 pub use self::drawable::Drawable;
 pub use self::font::{Font, FontRef, Info as FontInfo};
 pub use self::glyph::Glyph;
-pub use self::image::Image;
+pub use self::gradient::{Gradient, GradientStyle};
+pub use self::hot_reload::HotReload;
+pub use self::image::{Image, ResizeFilter};
+pub use self::image_diff::{image_diff, ImageDiff};
+pub use self::image_encoding::ImageFormat;
+pub use self::nine_slice_sprite::NineSliceSprite;
 pub use self::primitive_type::PrimitiveType;
-pub use self::rect::{FloatRect, IntRect, Rect};
+pub use self::rect::{FloatRect, IntRect, Rect, TryFromRectError};
 pub use self::rectangle_shape::RectangleShape;
 pub use self::render_states::RenderStates;
 pub use self::render_target::RenderTarget;
 pub use self::render_texture::RenderTexture;
 pub use self::render_window::{Events, RenderWindow};
+pub use self::scene_node::SceneNode;
 pub use self::shader::Shader;
 pub use self::shape::Shape;
+pub use self::software_render_target::SoftwareRenderTarget;
 pub use self::sprite::Sprite;
 pub use self::text::Text;
 pub use self::text_style::TextStyle;
 pub use self::texture::{Texture, TextureRef};
-pub use self::transform::Transform;
+pub use self::texture_atlas::{pack_rects, PackedAtlas, TextureAtlas};
+pub use self::tile_map::TileMap;
 pub use self::transformable::Transformable;
 pub use self::vertex::Vertex;
 pub use self::vertex_array::{VertexArray, Vertices};
 pub use self::view::{View, ViewRef};
 
+// Transform doesn't need the graphics library, it lives in system
+pub use system::Transform;
+
 mod drawable;
 mod shape;
 mod transformable;
@@ -46,7 +58,11 @@ mod color;
 mod font;
 mod view;
 mod image;
+mod image_diff;
+mod image_encoding;
 mod sprite;
+mod animated_sprite;
+mod nine_slice_sprite;
 mod circle_shape;
 mod rectangle_shape;
 mod convex_shape;
@@ -57,4 +73,11 @@ mod render_texture;
 mod custom_shape;
 mod rect;
 mod glyph;
+mod gradient;
+mod hot_reload;
+mod scene_node;
+mod texture_atlas;
+mod tile_map;
+mod rasterizer;
+mod software_render_target;
 pub mod glsl;
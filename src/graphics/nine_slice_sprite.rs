@@ -0,0 +1,285 @@
+use graphics::{Color, Drawable, FloatRect, IntRect, PrimitiveType, RenderStates, RenderTarget,
+               TextureRef, Transform, Transformable, Vertex, VertexArray};
+use graphics::transformable::TransformableState;
+use system::Vector2f;
+
+/// A sprite that stretches only the middle of its texture, for UI panels and buttons.
+///
+/// The texture rect is cut in nine parts by the inner rect. When the sprite is resized,
+/// the corners keep their size, the edges stretch along their length, and the center
+/// stretches in both directions, so borders and rounded corners don't get distorted.
+/// If the sprite is smaller than its borders, they are shrunk proportionally.
+///
+/// Like `Sprite`, it is `Transformable`, and its color modulates its texture.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{IntRect, NineSliceSprite, RenderTarget, RenderWindow, Texture,
+///                      Transformable};
+/// use sfml::window::style;
+///
+/// let mut window = RenderWindow::new((800, 600), "Panel", style::CLOSE, &Default::default());
+/// // A 48x48 panel texture with 16 pixel borders
+/// let texture = Texture::from_file("panel.png").unwrap();
+/// let mut panel = NineSliceSprite::new(&texture, IntRect::new(16, 16, 16, 16), (300., 200.));
+/// panel.set_position((250., 200.));
+/// window.draw(&panel);
+/// window.display();
+/// ```
+#[derive(Debug)]
+pub struct NineSliceSprite<'s> {
+    texture: &'s TextureRef,
+    texture_rect: IntRect,
+    inner_rect: IntRect,
+    size: Vector2f,
+    color: Color,
+    vertices: VertexArray,
+    transformable: TransformableState,
+}
+
+impl<'s> NineSliceSprite<'s> {
+    /// Create a sprite of `size` showing the whole `texture`.
+    ///
+    /// `inner_rect` is the stretchable part of the texture rect, relative to its top-left
+    /// corner.
+    pub fn new<S: Into<Vector2f>>(texture: &'s TextureRef,
+                                  inner_rect: IntRect,
+                                  size: S)
+                                  -> NineSliceSprite<'s> {
+        let texture_size = texture.size();
+        let mut sprite = NineSliceSprite {
+            texture,
+            texture_rect: IntRect::new(0, 0, texture_size.x as i32, texture_size.y as i32),
+            inner_rect,
+            size: size.into(),
+            color: Color::white(),
+            vertices: VertexArray::new(PrimitiveType::Triangles, 0),
+            transformable: TransformableState::default(),
+        };
+        sprite.update_vertices();
+        sprite
+    }
+
+    /// Change the texture.
+    ///
+    /// If `reset_rect` is true, the texture rect is set to the whole new texture.
+    pub fn set_texture(&mut self, texture: &'s TextureRef, reset_rect: bool) {
+        self.texture = texture;
+        if reset_rect {
+            let size = texture.size();
+            self.set_texture_rect(&IntRect::new(0, 0, size.x as i32, size.y as i32));
+        }
+    }
+
+    /// Return the texture.
+    pub fn texture(&self) -> &'s TextureRef {
+        self.texture
+    }
+
+    /// Set the part of the texture to display, the whole texture by default.
+    pub fn set_texture_rect(&mut self, rect: &IntRect) {
+        self.texture_rect = *rect;
+        self.update_vertices();
+    }
+
+    /// Return the part of the texture to display.
+    pub fn texture_rect(&self) -> IntRect {
+        self.texture_rect
+    }
+
+    /// Set the stretchable part of the texture rect, relative to its top-left corner.
+    ///
+    /// It is clamped to the texture rect.
+    pub fn set_inner_rect(&mut self, rect: &IntRect) {
+        self.inner_rect = *rect;
+        self.update_vertices();
+    }
+
+    /// Return the stretchable part of the texture rect.
+    pub fn inner_rect(&self) -> IntRect {
+        self.inner_rect
+    }
+
+    /// Set the size of the sprite, before its transform.
+    pub fn set_size<S: Into<Vector2f>>(&mut self, size: S) {
+        self.size = size.into();
+        self.update_vertices();
+    }
+
+    /// Return the size of the sprite, before its transform.
+    pub fn size(&self) -> Vector2f {
+        self.size
+    }
+
+    /// Set the color modulating the texture, white by default.
+    pub fn set_color(&mut self, color: &Color) {
+        self.color = *color;
+        for i in 0..self.vertices.vertex_count() {
+            self.vertices[i].color = *color;
+        }
+    }
+
+    /// Return the color modulating the texture.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Return the bounds of the sprite, before its transform.
+    pub fn local_bounds(&self) -> FloatRect {
+        FloatRect::new(0., 0., self.size.x.max(0.), self.size.y.max(0.))
+    }
+
+    /// Return the bounds of the sprite in the coordinates of its parent, after its
+    /// transform.
+    pub fn global_bounds(&self) -> FloatRect {
+        self.transform().transform_rect(&self.local_bounds())
+    }
+
+    /// Return the vertices of the nine parts, as triangles in local coordinates.
+    pub fn vertex_array(&self) -> &VertexArray {
+        &self.vertices
+    }
+
+    fn update_vertices(&mut self) {
+        let vertices = slice_vertices(self.texture_rect, self.inner_rect, self.size, self.color);
+        self.vertices.resize(vertices.len());
+        for (i, vertex) in vertices.into_iter().enumerate() {
+            self.vertices[i] = vertex;
+        }
+    }
+}
+
+/// Return the 9 quads of a nine-slice sprite, as 54 vertices forming triangles.
+fn slice_vertices(texture_rect: IntRect,
+                  inner_rect: IntRect,
+                  size: Vector2f,
+                  color: Color)
+                  -> Vec<Vertex> {
+    let (width, height) = (texture_rect.width.abs() as f32, texture_rect.height.abs() as f32);
+    let clamp = |value: i32, max: f32| (value as f32).max(0.).min(max);
+    let (inner_left, inner_top) = (clamp(inner_rect.left, width), clamp(inner_rect.top, height));
+    let inner_right = clamp(inner_rect.left + inner_rect.width, width).max(inner_left);
+    let inner_bottom = clamp(inner_rect.top + inner_rect.height, height).max(inner_top);
+
+    // Positions and texture coordinates of the cuts along each axis
+    let cuts = |length: f32, start: f32, end: f32, texture_length: f32, texture_start: i32| {
+        let length = length.max(0.);
+        let (first, last) = (start, texture_length - end);
+        let shrink = if first + last > length { length / (first + last) } else { 1. };
+        let t = texture_start as f32;
+        ([0., first * shrink, length - last * shrink, length],
+         [t, t + start, t + end, t + texture_length])
+    };
+    let (xs, us) = cuts(size.x, inner_left, inner_right, width, texture_rect.left);
+    let (ys, vs) = cuts(size.y, inner_top, inner_bottom, height, texture_rect.top);
+
+    let mut vertices = Vec::with_capacity(54);
+    for row in 0..3 {
+        for column in 0..3 {
+            let corner = |c: usize, r: usize| {
+                Vertex::new((xs[c], ys[r]), color, Vector2f::new(us[c], vs[r]))
+            };
+            let top_left = corner(column, row);
+            let top_right = corner(column + 1, row);
+            let bottom_left = corner(column, row + 1);
+            let bottom_right = corner(column + 1, row + 1);
+            vertices.extend_from_slice(&[top_left, top_right, bottom_left, bottom_left,
+                                         top_right, bottom_right]);
+        }
+    }
+    vertices
+}
+
+impl<'s> Drawable for NineSliceSprite<'s> {
+    fn draw<'se, 'tex, 'sh, 'shte>(&'se self,
+                                   target: &mut dyn RenderTarget,
+                                   states: RenderStates<'tex, 'sh, 'shte>)
+        where 'se: 'sh
+    {
+        let states = RenderStates::new(states.blend_mode,
+                                       states.transform * self.transform(),
+                                       Some(self.texture),
+                                       states.shader);
+        target.draw_vertex_array(&self.vertices, states)
+    }
+}
+
+impl<'s> Transformable for NineSliceSprite<'s> {
+    fn set_position<P: Into<Vector2f>>(&mut self, position: P) {
+        self.transformable.set_position(position)
+    }
+    fn set_rotation(&mut self, angle: f32) {
+        self.transformable.set_rotation(angle)
+    }
+    fn set_scale<S: Into<Vector2f>>(&mut self, scale: S) {
+        self.transformable.set_scale(scale)
+    }
+    fn set_origin<O: Into<Vector2f>>(&mut self, origin: O) {
+        self.transformable.set_origin(origin)
+    }
+    fn position(&self) -> Vector2f {
+        self.transformable.position()
+    }
+    fn rotation(&self) -> f32 {
+        self.transformable.rotation()
+    }
+    fn get_scale(&self) -> Vector2f {
+        self.transformable.get_scale()
+    }
+    fn origin(&self) -> Vector2f {
+        self.transformable.origin()
+    }
+    fn move_<O: Into<Vector2f>>(&mut self, offset: O) {
+        self.transformable.move_(offset)
+    }
+    fn rotate(&mut self, angle: f32) {
+        self.transformable.rotate(angle)
+    }
+    fn scale<F: Into<Vector2f>>(&mut self, factors: F) {
+        self.transformable.scale(factors)
+    }
+    fn transform(&self) -> Transform {
+        self.transformable.transform()
+    }
+    fn inverse_transform(&self) -> Transform {
+        self.transformable.inverse_transform()
+    }
+}
+
+#[test]
+fn nine_slices() {
+    let texture_rect = IntRect::new(100, 50, 48, 40);
+    let inner = IntRect::new(16, 10, 16, 20);
+    let vertices = slice_vertices(texture_rect, inner, Vector2f::new(200., 100.), Color::red());
+    assert_eq!(vertices.len(), 54);
+    assert!(vertices.iter().all(|v| v.color == Color::red()));
+    let corners = |quad: usize| {
+        let v = &vertices[quad * 6..quad * 6 + 6];
+        (v[0].position, v[5].position, v[0].tex_coords, v[5].tex_coords)
+    };
+    // Top-left corner keeps its size
+    assert_eq!(corners(0),
+               (Vector2f::new(0., 0.),
+                Vector2f::new(16., 10.),
+                Vector2f::new(100., 50.),
+                Vector2f::new(116., 60.)));
+    // The center stretches
+    assert_eq!(corners(4),
+               (Vector2f::new(16., 10.),
+                Vector2f::new(184., 90.),
+                Vector2f::new(116., 60.),
+                Vector2f::new(132., 80.)));
+    // Bottom-right corner
+    assert_eq!(corners(8),
+               (Vector2f::new(184., 90.),
+                Vector2f::new(200., 100.),
+                Vector2f::new(132., 80.),
+                Vector2f::new(148., 90.)));
+
+    // Borders shrink when the sprite is smaller than them
+    let small = slice_vertices(texture_rect, inner, Vector2f::new(16., 100.), Color::white());
+    assert_eq!(small[0].position, Vector2f::new(0., 0.));
+    assert_eq!(small[5].position, Vector2f::new(8., 10.));
+    assert_eq!(small[6 * 4 + 5].position, Vector2f::new(8., 90.));
+}
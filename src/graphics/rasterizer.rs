@@ -0,0 +1,378 @@
+//! Drawing primitives on the CPU, for `SoftwareRenderTarget`.
+
+use graphics::{BlendMode, Color, FloatRect, IntRect, PrimitiveType, Transform, Vertex};
+use system::Vector2f;
+
+/// The pixels of a texture, sampled with the nearest texel.
+#[derive(Debug, Clone)]
+pub struct Texels {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Color>,
+    pub repeated: bool,
+}
+
+impl Texels {
+    /// Return the texel at `coords`, in pixels, as SFML's texture coordinates are.
+    fn sample(&self, coords: Vector2f) -> Color {
+        if self.width == 0 || self.height == 0 {
+            return Color::white();
+        }
+        let (width, height) = (self.width as i64, self.height as i64);
+        let (x, y) = (coords.x.floor() as i64, coords.y.floor() as i64);
+        let (x, y) = if self.repeated {
+            (x.rem_euclid(width), y.rem_euclid(height))
+        } else {
+            (x.clamp(0, width - 1), y.clamp(0, height - 1))
+        };
+        self.pixels[(y * width + x) as usize]
+    }
+}
+
+/// How primitives are drawn: the equivalent of `RenderStates`, with the view applied.
+#[derive(Debug)]
+pub struct DrawState<'a> {
+    /// Transform from the coordinates of the vertices to pixels.
+    pub transform: Transform,
+    /// Pixels outside of this rectangle are left untouched.
+    pub clip: IntRect,
+    pub texture: Option<&'a Texels>,
+    pub blend_mode: BlendMode,
+}
+
+/// A vertex transformed to pixel coordinates.
+#[derive(Clone, Copy)]
+struct Point {
+    position: Vector2f,
+    color: [f32; 4],
+    tex_coords: Vector2f,
+}
+
+/// An RGBA pixel buffer.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Color>,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, color: Color) -> Canvas {
+        Canvas {
+            width,
+            height,
+            pixels: vec![color; width as usize * height as usize],
+        }
+    }
+
+    pub fn clear(&mut self, color: Color) {
+        for pixel in &mut self.pixels {
+            *pixel = color;
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        self.pixels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Draw primitives as a GPU would, with a pixel center rasterization and the top-left rule
+    /// for triangles, so that triangles sharing an edge don't overlap.
+    pub fn draw(&mut self, vertices: &[Vertex], primitive_type: PrimitiveType, state: &DrawState) {
+        let points: Vec<Point> = vertices.iter()
+            .map(|v| {
+                Point {
+                    position: state.transform.transform_point(&v.position),
+                    color: [v.color.r as f32, v.color.g as f32, v.color.b as f32, v.color.a as f32],
+                    tex_coords: v.tex_coords,
+                }
+            })
+            .collect();
+        let clip = self.clip(state.clip);
+        let p = &points;
+        match primitive_type {
+            PrimitiveType::Points => {
+                for point in p {
+                    self.fragment(point.position.x.floor() as i32,
+                                  point.position.y.floor() as i32,
+                                  point,
+                                  &clip,
+                                  state);
+                }
+            }
+            PrimitiveType::Lines => {
+                for pair in p.chunks(2).filter(|c| c.len() == 2) {
+                    self.line(&pair[0], &pair[1], &clip, state);
+                }
+            }
+            PrimitiveType::LineStrip => {
+                for pair in p.windows(2) {
+                    self.line(&pair[0], &pair[1], &clip, state);
+                }
+            }
+            PrimitiveType::Triangles => {
+                for t in p.chunks(3).filter(|c| c.len() == 3) {
+                    self.triangle(&t[0], &t[1], &t[2], &clip, state);
+                }
+            }
+            PrimitiveType::TriangleStrip => {
+                for t in p.windows(3) {
+                    self.triangle(&t[0], &t[1], &t[2], &clip, state);
+                }
+            }
+            PrimitiveType::TriangleFan => {
+                for i in 2..p.len() {
+                    self.triangle(&p[0], &p[i - 1], &p[i], &clip, state);
+                }
+            }
+            PrimitiveType::Quads => {
+                for q in p.chunks(4).filter(|c| c.len() == 4) {
+                    self.triangle(&q[0], &q[1], &q[2], &clip, state);
+                    self.triangle(&q[0], &q[2], &q[3], &clip, state);
+                }
+            }
+        }
+    }
+
+    /// Return the clip rectangle restricted to the canvas, as `(left, top, right, bottom)`
+    /// with exclusive right and bottom.
+    fn clip(&self, clip: IntRect) -> (i32, i32, i32, i32) {
+        let right = (clip.left + clip.width).min(self.width as i32);
+        let bottom = (clip.top + clip.height).min(self.height as i32);
+        (clip.left.max(0), clip.top.max(0), right, bottom)
+    }
+
+    fn fragment(&mut self,
+                x: i32,
+                y: i32,
+                point: &Point,
+                clip: &(i32, i32, i32, i32),
+                state: &DrawState) {
+        if x < clip.0 || y < clip.1 || x >= clip.2 || y >= clip.3 {
+            return;
+        }
+        let c = &point.color;
+        let mut color = Color::rgba(c[0].round() as u8,
+                                    c[1].round() as u8,
+                                    c[2].round() as u8,
+                                    c[3].round() as u8);
+        if let Some(texture) = state.texture {
+            color = modulate(color, texture.sample(point.tex_coords));
+        }
+        let index = y as usize * self.width as usize + x as usize;
+        self.pixels[index] = state.blend_mode.blend(color, self.pixels[index]);
+    }
+
+    fn line(&mut self, a: &Point, b: &Point, clip: &(i32, i32, i32, i32), state: &DrawState) {
+        let delta = b.position - a.position;
+        let steps = delta.x.abs().max(delta.y.abs()).round().max(1.) as u32;
+        for step in 0..steps {
+            let t = (step as f32 + 0.5) / steps as f32;
+            let point = interpolate(&[a, b], &[1. - t, t]);
+            self.fragment(point.position.x.floor() as i32,
+                          point.position.y.floor() as i32,
+                          &point,
+                          clip,
+                          state);
+        }
+    }
+
+    fn triangle(&mut self,
+                a: &Point,
+                b: &Point,
+                c: &Point,
+                clip: &(i32, i32, i32, i32),
+                state: &DrawState) {
+        let area = edge(a.position, b.position, c.position);
+        if area == 0. || !area.is_finite() {
+            return;
+        }
+        // Make the winding consistent, so that the inside is where the edge functions are positive
+        let (b, c, area) = if area < 0. { (c, b, -area) } else { (b, c, area) };
+        let points = [a, b, c];
+
+        let xs = [a.position.x, b.position.x, c.position.x];
+        let ys = [a.position.y, b.position.y, c.position.y];
+        let min_x = (xs.iter().cloned().fold(f32::INFINITY, f32::min) - 0.5).ceil() as i32;
+        let max_x = (xs.iter().cloned().fold(f32::NEG_INFINITY, f32::max) - 0.5).floor() as i32;
+        let min_y = (ys.iter().cloned().fold(f32::INFINITY, f32::min) - 0.5).ceil() as i32;
+        let max_y = (ys.iter().cloned().fold(f32::NEG_INFINITY, f32::max) - 0.5).floor() as i32;
+
+        let edges = [(b.position, c.position), (c.position, a.position), (a.position, b.position)];
+        for y in min_y.max(clip.1)..(max_y + 1).min(clip.3) {
+            for x in min_x.max(clip.0)..(max_x + 1).min(clip.2) {
+                let center = Vector2f::new(x as f32 + 0.5, y as f32 + 0.5);
+                let mut weights = [0.; 3];
+                let inside = edges.iter().zip(weights.iter_mut()).all(|(&(from, to), weight)| {
+                    let w = edge(from, to, center);
+                    *weight = w / area;
+                    w > 0. || (w == 0. && is_top_left(from, to))
+                });
+                if inside {
+                    let point = interpolate(&points, &weights);
+                    self.fragment(x, y, &point, clip, state);
+                }
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `(a, b, p)`.
+///
+/// With SFML's y axis pointing down, it is positive when `p` is clockwise from `a -> b`.
+fn edge(a: Vector2f, b: Vector2f, p: Vector2f) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Tell whether a (clockwise) edge is a top or left edge, whose pixels belong to the triangle.
+fn is_top_left(from: Vector2f, to: Vector2f) -> bool {
+    (from.y == to.y && to.x > from.x) || to.y < from.y
+}
+
+fn interpolate(points: &[&Point], weights: &[f32]) -> Point {
+    let mut result = Point {
+        position: Vector2f::new(0., 0.),
+        color: [0.; 4],
+        tex_coords: Vector2f::new(0., 0.),
+    };
+    for (point, &weight) in points.iter().zip(weights) {
+        result.position += point.position * weight;
+        result.tex_coords += point.tex_coords * weight;
+        for (component, &value) in result.color.iter_mut().zip(&point.color) {
+            *component += value * weight;
+        }
+    }
+    for component in &mut result.color {
+        *component = component.clamp(0., 255.);
+    }
+    result
+}
+
+/// Component-wise modulation, as `Color * Color`.
+fn modulate(a: Color, b: Color) -> Color {
+    let mul = |x: u8, y: u8| (x as u32 * y as u32 / 255) as u8;
+    Color::rgba(mul(a.r, b.r), mul(a.g, b.g), mul(a.b, b.b), mul(a.a, b.a))
+}
+
+/// Return the bounding rectangle of points.
+fn bounds(points: &[Vector2f]) -> FloatRect {
+    let first = points.first().cloned().unwrap_or_else(|| Vector2f::new(0., 0.));
+    let (mut min, mut max) = (first, first);
+    for p in points {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    FloatRect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+}
+
+/// Return the triangle fan filling a shape, as SFML builds it.
+pub fn shape_fill(points: &[Vector2f], color: Color, texture_rect: IntRect) -> Vec<Vertex> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let bounds = bounds(points);
+    let tex_coords = |p: Vector2f| {
+        let x_ratio = if bounds.width > 0. { (p.x - bounds.left) / bounds.width } else { 0. };
+        let y_ratio = if bounds.height > 0. { (p.y - bounds.top) / bounds.height } else { 0. };
+        Vector2f::new(texture_rect.left as f32 + texture_rect.width as f32 * x_ratio,
+                      texture_rect.top as f32 + texture_rect.height as f32 * y_ratio)
+    };
+    let center = Vector2f::new(bounds.left + bounds.width / 2., bounds.top + bounds.height / 2.);
+    let mut vertices = Vec::with_capacity(points.len() + 2);
+    vertices.push(Vertex::new(center, color, tex_coords(center)));
+    for &p in points.iter().chain(points.first()) {
+        vertices.push(Vertex::new(p, color, tex_coords(p)));
+    }
+    vertices
+}
+
+/// Return the triangle strip of a shape's outline, as SFML builds it.
+pub fn shape_outline(points: &[Vector2f], thickness: f32, color: Color) -> Vec<Vertex> {
+    if points.len() < 3 || thickness == 0. {
+        return Vec::new();
+    }
+    let bounds = bounds(points);
+    let center = Vector2f::new(bounds.left + bounds.width / 2., bounds.top + bounds.height / 2.);
+    let normal = |from: Vector2f, to: Vector2f| {
+        let n = Vector2f::new(from.y - to.y, to.x - from.x);
+        let n = n.normalize();
+        // Make the normal point outward
+        if n.dot(center - from) > 0. { -n } else { n }
+    };
+    let count = points.len();
+    let mut vertices = Vec::with_capacity(count * 2 + 2);
+    for i in 0..count {
+        let previous = points[(i + count - 1) % count];
+        let current = points[i];
+        let next = points[(i + 1) % count];
+        let (n1, n2) = (normal(previous, current), normal(current, next));
+        let factor = 1. + n1.dot(n2);
+        let offset = (n1 + n2) / factor * thickness;
+        vertices.push(Vertex::with_pos_color(current, color));
+        vertices.push(Vertex::with_pos_color(current + offset, color));
+    }
+    let (first, second) = (vertices[0], vertices[1]);
+    vertices.push(first);
+    vertices.push(second);
+    vertices
+}
+
+#[test]
+fn rasterize_triangles_and_lines() {
+    use graphics::blend_mode;
+
+    let mut canvas = Canvas::new(4, 4, Color::black());
+    let state = DrawState {
+        transform: Transform::identity(),
+        clip: IntRect::new(0, 0, 4, 4),
+        texture: None,
+        blend_mode: blend_mode::ADD,
+    };
+    // Two triangles sharing a diagonal, with additive blending: no pixel is drawn twice
+    let quad = [Vertex::with_pos_color((0., 0.), Color::rgb(100, 0, 0)),
+                Vertex::with_pos_color((4., 0.), Color::rgb(100, 0, 0)),
+                Vertex::with_pos_color((4., 4.), Color::rgb(100, 0, 0)),
+                Vertex::with_pos_color((0., 4.), Color::rgb(100, 0, 0))];
+    canvas.draw(&quad, PrimitiveType::Quads, &state);
+    assert!(canvas.pixels.iter().all(|&p| p == Color::rgb(100, 0, 0)));
+
+    let texels = Texels {
+        width: 2,
+        height: 1,
+        pixels: vec![Color::white(), Color::rgba(0, 0, 255, 128)],
+        repeated: true,
+    };
+    let state = DrawState {
+        transform: Transform::identity().translated(1., 1.),
+        clip: IntRect::new(0, 0, 3, 4),
+        texture: Some(&texels),
+        blend_mode: blend_mode::NONE,
+    };
+    let strip = [Vertex::new((0., 0.), Color::green(), Vector2f::new(0., 0.)),
+                 Vertex::new((0., 1.), Color::green(), Vector2f::new(0., 1.)),
+                 Vertex::new((4., 0.), Color::green(), Vector2f::new(4., 0.)),
+                 Vertex::new((4., 1.), Color::green(), Vector2f::new(4., 1.))];
+    canvas.draw(&strip, PrimitiveType::TriangleStrip, &state);
+    assert_eq!(canvas.pixel(0, 1), Color::rgb(100, 0, 0));
+    assert_eq!(canvas.pixel(1, 1), Color::green());
+    assert_eq!(canvas.pixel(2, 1), Color::rgba(0, 0, 0, 128));
+    assert_eq!(canvas.pixel(3, 1), Color::rgb(100, 0, 0));
+
+    let line = [Vertex::with_pos_color((-1., 2.5), Color::white()),
+                Vertex::with_pos_color((3., 2.5), Color::blue())];
+    canvas.draw(&line, PrimitiveType::Lines, &state);
+    assert_eq!(canvas.pixel(1, 3), Color::rgb(159, 159, 255));
+    assert_eq!(canvas.pixel(3, 3), Color::rgb(100, 0, 0));
+
+    let square = [Vector2f::new(0., 0.),
+                  Vector2f::new(2., 0.),
+                  Vector2f::new(2., 2.),
+                  Vector2f::new(0., 2.)];
+    let fill = shape_fill(&square, Color::red(), IntRect::new(0, 0, 10, 10));
+    assert_eq!(fill.len(), 6);
+    assert_eq!(fill[0].tex_coords, Vector2f::new(5., 5.));
+    let outline = shape_outline(&square, 1., Color::red());
+    assert_eq!(outline.len(), 10);
+    assert_eq!(outline[1].position, Vector2f::new(-1., -1.));
+}
@@ -1,9 +1,31 @@
 use graphics::csfml_graphics_sys as ffi;
-use std::ops::{Add, Sub};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Div, Sub};
 use system::Vector2;
 use system::raw_conv::{FromRaw, Raw};
 
 /// Utility type for manipulating 2D axis-aligned rectangles.
+///
+/// Like in SFML, rectangles with negative dimensions are allowed: the operations work on
+/// the area between `(left, top)` and `(left + width, top + height)`, and the rectangles
+/// they return have positive dimensions.
+///
+/// # Usage example
+///
+/// ```
+/// # use sfml::graphics::{FloatRect, IntRect};
+/// # use sfml::system::Vector2f;
+/// use std::convert::TryFrom;
+///
+/// let a = FloatRect::new(0., 0., 10., 10.);
+/// let b = FloatRect::new(20., 5., 10., 10.);
+/// assert_eq!(a.union(&b), FloatRect::new(0., 0., 30., 15.));
+/// assert_eq!(a.center(), Vector2f::new(5., 5.));
+/// assert_eq!(a.inflate(1., 2.), FloatRect::new(-1., -2., 12., 14.));
+/// assert_eq!(IntRect::try_from(a), Ok(IntRect::new(0, 0, 10, 10)));
+/// ```
 #[repr(C)]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Default)]
 pub struct Rect<T> {
@@ -44,7 +66,97 @@ impl<T> Rect<T> {
     }
 }
 
+impl<T: Copy> Rect<T> {
+    /// Returns the position of the top-left corner of the rectangle.
+    pub fn position(self) -> Vector2<T> {
+        Vector2::new(self.left, self.top)
+    }
+
+    /// Returns the size of the rectangle.
+    pub fn size(self) -> Vector2<T> {
+        Vector2::new(self.width, self.height)
+    }
+}
+
 impl<T: PartialOrd + Add<Output = T> + Sub<Output = T> + Copy> Rect<T> {
+    /// Returns the x coordinate of the right edge, `left + width`.
+    pub fn right(self) -> T {
+        self.left + self.width
+    }
+
+    /// Returns the y coordinate of the bottom edge, `top + height`.
+    pub fn bottom(self) -> T {
+        self.top + self.height
+    }
+
+    /// Returns the four corners of the rectangle: top-left, top-right, bottom-right and
+    /// bottom-left.
+    pub fn corners(self) -> [Vector2<T>; 4] {
+        let (right, bottom) = (self.right(), self.bottom());
+        [Vector2::new(self.left, self.top),
+         Vector2::new(right, self.top),
+         Vector2::new(right, bottom),
+         Vector2::new(self.left, bottom)]
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    pub fn union(self, other: &Rect<T>) -> Rect<T> {
+        let (r1_min_x, r1_max_x) = min_max(self.left, self.right());
+        let (r1_min_y, r1_max_y) = min_max(self.top, self.bottom());
+        let (r2_min_x, r2_max_x) = min_max(other.left, other.right());
+        let (r2_min_y, r2_max_y) = min_max(other.top, other.bottom());
+        let left = min(r1_min_x, r2_min_x);
+        let top = min(r1_min_y, r2_min_y);
+        Rect::new(left,
+                  top,
+                  max(r1_max_x, r2_max_x) - left,
+                  max(r1_max_y, r2_max_y) - top)
+    }
+
+    /// Returns the smallest rectangle containing this rectangle and `point`.
+    ///
+    /// The point ends up on the edge of the rectangle, so `contains` may still reject it.
+    pub fn expand_to_include(self, point: Vector2<T>) -> Rect<T> {
+        let (min_x, max_x) = min_max(self.left, self.right());
+        let (min_y, max_y) = min_max(self.top, self.bottom());
+        let left = min(min_x, point.x);
+        let top = min(min_y, point.y);
+        Rect::new(left, top, max(max_x, point.x) - left, max(max_y, point.y) - top)
+    }
+
+    /// Returns the rectangle grown by `dx` on the left and right, and by `dy` on the top
+    /// and bottom.
+    pub fn inflate(self, dx: T, dy: T) -> Rect<T> {
+        let (min_x, max_x) = min_max(self.left, self.right());
+        let (min_y, max_y) = min_max(self.top, self.bottom());
+        let (left, right) = min_max(min_x - dx, max_x + dx);
+        let (top, bottom) = min_max(min_y - dy, max_y + dy);
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Returns the rectangle shrunk by `dx` on the left and right, and by `dy` on the top
+    /// and bottom.
+    ///
+    /// Shrinking by more than half the size makes the edges cross, the result then spans
+    /// between them.
+    pub fn deflate(self, dx: T, dy: T) -> Rect<T> {
+        let (min_x, max_x) = min_max(self.left, self.right());
+        let (min_y, max_y) = min_max(self.top, self.bottom());
+        let (left, right) = min_max(min_x + dx, max_x - dx);
+        let (top, bottom) = min_max(min_y + dy, max_y - dy);
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Returns the point of the rectangle closest to `point`.
+    ///
+    /// The point is clamped to the closed area, right and bottom edges included.
+    pub fn clamp_point(self, point: Vector2<T>) -> Vector2<T> {
+        let (min_x, max_x) = min_max(self.left, self.right());
+        let (min_y, max_y) = min_max(self.top, self.bottom());
+        Vector2::new(max(min_x, min(point.x, max_x)),
+                     max(min_y, min(point.y, max_y)))
+    }
+
     /// Check if a point is inside the rectangle's area.
     #[inline]
     pub fn contains(self, point: Vector2<T>) -> bool {
@@ -85,6 +197,94 @@ impl<T: PartialOrd + Add<Output = T> + Sub<Output = T> + Copy> Rect<T> {
     }
 }
 
+impl<T: PartialEq + Default> Rect<T> {
+    /// Tells whether the rectangle has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width == T::default() || self.height == T::default()
+    }
+}
+
+impl<T: Add<Output = T> + Div<Output = T> + From<u8> + Copy> Rect<T> {
+    /// Returns the center of the rectangle.
+    ///
+    /// For an `IntRect`, the coordinates are rounded toward zero.
+    pub fn center(self) -> Vector2<T> {
+        let two = T::from(2);
+        Vector2::new(self.left + self.width / two, self.top + self.height / two)
+    }
+}
+
+impl FloatRect {
+    /// Returns the smallest `IntRect` containing this rectangle.
+    ///
+    /// Coordinates out of the range of `i32` saturate.
+    pub fn enclosing_int_rect(self) -> IntRect {
+        let (min_x, max_x) = min_max(self.left, self.right());
+        let (min_y, max_y) = min_max(self.top, self.bottom());
+        let (left, top) = (min_x.floor() as i32, min_y.floor() as i32);
+        IntRect::new(left,
+                     top,
+                     (max_x.ceil() as i32).saturating_sub(left),
+                     (max_y.ceil() as i32).saturating_sub(top))
+    }
+}
+
+/// The error returned when a rectangle can't be converted exactly to another coordinate type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromRectError(());
+
+impl fmt::Display for TryFromRectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("rectangle coordinates can't be represented exactly in the target type")
+    }
+}
+
+impl Error for TryFromRectError {}
+
+fn int_to_f32(value: i32) -> Result<f32, TryFromRectError> {
+    // Compared as f64, which holds both exactly, as `as i32` would saturate 2^31 to i32::MAX
+    if f64::from(value as f32) == f64::from(value) {
+        Ok(value as f32)
+    } else {
+        Err(TryFromRectError(()))
+    }
+}
+
+fn f32_to_int(value: f32) -> Result<i32, TryFromRectError> {
+    // i32::MAX isn't representable as f32, 2^31 is the first value out of range
+    if value.fract() == 0. && value >= i32::MIN as f32 && value < 2_147_483_648. {
+        Ok(value as i32)
+    } else {
+        Err(TryFromRectError(()))
+    }
+}
+
+impl TryFrom<IntRect> for FloatRect {
+    type Error = TryFromRectError;
+
+    /// Fails if a coordinate is too large to be represented exactly by a `f32`.
+    fn try_from(src: IntRect) -> Result<FloatRect, TryFromRectError> {
+        Ok(FloatRect::new(int_to_f32(src.left)?,
+                          int_to_f32(src.top)?,
+                          int_to_f32(src.width)?,
+                          int_to_f32(src.height)?))
+    }
+}
+
+impl TryFrom<FloatRect> for IntRect {
+    type Error = TryFromRectError;
+
+    /// Fails if a coordinate isn't an integer in the range of `i32`.
+    ///
+    /// See `FloatRect::enclosing_int_rect` for a rounding conversion.
+    fn try_from(src: FloatRect) -> Result<IntRect, TryFromRectError> {
+        Ok(IntRect::new(f32_to_int(src.left)?,
+                        f32_to_int(src.top)?,
+                        f32_to_int(src.width)?,
+                        f32_to_int(src.height)?))
+    }
+}
+
 #[inline]
 fn min<T: PartialOrd>(a: T, b: T) -> T {
     if a < b { a } else { b }
@@ -149,3 +349,28 @@ impl FromRaw for FloatRect {
         }
     }
 }
+
+#[test]
+fn rect_operations() {
+    let r = IntRect::new(10, 10, -10, 20);
+    assert_eq!(r.union(&IntRect::new(5, 40, 1, 1)), IntRect::new(0, 10, 10, 31));
+    assert_eq!(r.expand_to_include(Vector2::new(-5, 0)),
+               IntRect::new(-5, 0, 15, 30));
+    assert_eq!(r.clamp_point(Vector2::new(100, -100)), Vector2::new(10, 10));
+    assert_eq!(r.deflate(1, 1), IntRect::new(1, 11, 8, 18));
+    assert_eq!(r.inflate(1, 1), IntRect::new(-1, 9, 12, 22));
+    assert_eq!(IntRect::new(0, 0, 4, 4).deflate(3, 0), IntRect::new(1, 0, 2, 4));
+    assert_eq!(r.center(), Vector2::new(5, 20));
+    assert_eq!(r.corners()[2], Vector2::new(0, 30));
+    assert!(IntRect::new(1, 1, 0, 5).is_empty());
+    assert!(!r.is_empty());
+
+    let f = FloatRect::new(0.5, -1.5, 2., 1.);
+    assert_eq!(f.enclosing_int_rect(), IntRect::new(0, -2, 3, 2));
+    assert!(IntRect::try_from(f).is_err());
+    assert!(FloatRect::try_from(IntRect::new(0, 0, (1 << 24) + 1, 1)).is_err());
+    assert!(FloatRect::try_from(IntRect::new(0, 0, i32::MAX, 1)).is_err());
+    assert_eq!(FloatRect::try_from(IntRect::new(0, 0, 1 << 25, i32::MIN)),
+               Ok(FloatRect::new(0., 0., 33_554_432., -2_147_483_648.)));
+    assert_eq!(FloatRect::try_from(r), Ok(FloatRect::new(10., 10., -10., 20.)));
+}
@@ -107,10 +107,12 @@ impl<'s> Transformable for RectangleShape<'s> {
         unsafe { ffi::sfRectangleShape_scale(self.rectangle_shape, factors.into().raw()) }
     }
     fn transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfRectangleShape_getTransform(self.rectangle_shape)) }
+        unsafe { Transform::from_raw(ffi::sfRectangleShape_getTransform(self.rectangle_shape)) }
     }
     fn inverse_transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfRectangleShape_getInverseTransform(self.rectangle_shape)) }
+        unsafe {
+            Transform::from_raw(ffi::sfRectangleShape_getInverseTransform(self.rectangle_shape))
+        }
     }
 }
 
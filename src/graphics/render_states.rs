@@ -87,7 +87,7 @@ impl<'te, 'sh, 'shte> Raw for RenderStates<'te, 'sh, 'shte> {
     fn raw(&self) -> Self::Raw {
         ffi::sfRenderStates {
             blendMode: self.blend_mode.raw(),
-            transform: self.transform.0,
+            transform: self.transform.raw(),
             texture: match self.texture {
                 Some(texture) => texture.raw(),
                 None => ptr::null_mut(),
@@ -1,10 +1,12 @@
 use csfml_system_sys::sfBool;
+use error::{self, Error, ErrorKind};
 use graphics::{CircleShape, Color, ConvexShape, CustomShape, Drawable, IntRect, PrimitiveType,
                RectangleShape, RenderStates, RenderTarget, Sprite, Text, TextureRef, Vertex,
                VertexArray, View, ViewRef};
 use graphics::csfml_graphics_sys as ffi;
 use sf_bool_ext::SfBoolExt;
 use system::{Vector2f, Vector2i, Vector2u};
+use system::err;
 use system::raw_conv::{FromRaw, Raw};
 
 /// Target for off-screen 2D rendering into a texture
@@ -22,14 +24,15 @@ impl RenderTexture {
     /// * depthBuffer - Do you want a depth-buffer attached?
     ///                 (useful only if you're doing 3D OpenGL on the rendertexture)
     ///
-    /// Return Some(RenderTexture) or None
-    pub fn new(width: u32, height: u32, depth_buffer: bool) -> Option<RenderTexture> {
-        let tex =
-            unsafe { ffi::sfRenderTexture_create(width, height, sfBool::from_bool(depth_buffer)) };
+    /// Return the new RenderTexture, or an error if it couldn't be allocated
+    pub fn new(width: u32, height: u32, depth_buffer: bool) -> error::Result<RenderTexture> {
+        let (tex, message) = err::capture("RenderTexture::new", || unsafe {
+            ffi::sfRenderTexture_create(width, height, sfBool::from_bool(depth_buffer))
+        });
         if tex.is_null() {
-            None
+            Err(Error::new(ErrorKind::ResourceCreation).with_message(message))
         } else {
-            Some(RenderTexture { render_texture: tex })
+            Ok(RenderTexture { render_texture: tex })
         }
     }
 
@@ -5,7 +5,7 @@ use graphics::{CircleShape, Color, ConvexShape, CustomShape, Drawable, IntRect,
 use graphics::csfml_graphics_sys as ffi;
 use sf_bool_ext::SfBoolExt;
 use std::marker::PhantomData;
-use system::{Vector2f, Vector2i, Vector2u};
+use system::{GameLoop, Time, Vector2f, Vector2i, Vector2u};
 use system::raw_conv::{FromRaw, Raw};
 use window::{ContextSettings, Event, Style, VideoMode};
 
@@ -365,6 +365,33 @@ impl RenderWindow {
     pub fn request_focus(&self) {
         unsafe { ffi::sfRenderWindow_requestFocus(self.render_window) }
     }
+
+    /// Runs the frames of `game_loop` until the window is closed.
+    ///
+    /// Each frame, the pending events are passed to `event`, then the loop runs the fixed
+    /// updates and the render (see `GameLoop::frame`), and finally displays the window.
+    pub fn run_game_loop<S, E, U, R>(&mut self,
+                                     game_loop: &mut GameLoop,
+                                     state: &mut S,
+                                     mut event: E,
+                                     mut update: U,
+                                     mut render: R)
+        where E: FnMut(&mut S, &mut RenderWindow, Event),
+              U: FnMut(&mut S, Time),
+              R: FnMut(&mut S, &mut RenderWindow, f32)
+    {
+        game_loop.reset();
+        while self.is_open() {
+            while let Some(e) = self.poll_event() {
+                event(state, self, e);
+            }
+            if !self.is_open() {
+                break;
+            }
+            game_loop.frame(state, &mut update, |state, alpha| render(state, self, alpha));
+            self.display();
+        }
+    }
 }
 
 impl Raw for RenderWindow {
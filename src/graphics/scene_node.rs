@@ -0,0 +1,325 @@
+use graphics::{Drawable, FloatRect, RenderStates, RenderTarget, Transform, Transformable};
+use graphics::transformable::TransformableState;
+use std::fmt;
+use system::Vector2f;
+
+/// A node of a scene graph.
+///
+/// Each node has its own position, rotation, scale and origin (it is `Transformable`),
+/// which are relative to its parent: moving a node moves all its children with it.
+/// A node may hold something to draw, and any number of child nodes. Nodes that hold nothing
+/// are useful to group other nodes.
+///
+/// When drawn, a node combines its transform with the one of the `RenderStates`, then draws
+/// its children with a negative z-order, its own drawable, and its other children.
+/// Children are sorted by increasing z-order, children with the same z-order are drawn
+/// in the order they were attached.
+///
+/// Nodes can be given a name, to find them and query their position in the scene later.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{CircleShape, RenderTarget, RenderWindow, SceneNode, Shape, Transformable};
+/// use sfml::window::style;
+///
+/// let mut window = RenderWindow::new((800, 600), "Scene", style::CLOSE, &Default::default());
+///
+/// let ship_shape = CircleShape::new(20., 3);
+/// let mut ship = SceneNode::with_drawable(ship_shape.clone(), ship_shape.local_bounds());
+/// ship.set_position((400., 300.));
+///
+/// // The turret follows the ship, and is drawn below it
+/// let turret_shape = CircleShape::new(5., 30);
+/// let mut turret = SceneNode::with_drawable(turret_shape.clone(), turret_shape.local_bounds());
+/// turret.set_name("turret");
+/// turret.set_position((15., 15.));
+/// turret.set_z_order(-1);
+/// ship.attach_child(turret);
+///
+/// ship.rotate(45.);
+/// println!("The turret is at {:?}", ship.world_bounds("turret"));
+/// window.draw(&ship);
+/// ```
+pub struct SceneNode<'s> {
+    name: String,
+    transformable: TransformableState,
+    z_order: i32,
+    drawable: Option<(Box<dyn Drawable + 's>, FloatRect)>,
+    children: Vec<SceneNode<'s>>,
+}
+
+impl<'s> SceneNode<'s> {
+    /// Create an empty node, with no drawable and no children.
+    pub fn new() -> SceneNode<'s> {
+        SceneNode {
+            name: String::new(),
+            transformable: TransformableState::default(),
+            z_order: 0,
+            drawable: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a node drawing `drawable`.
+    ///
+    /// See `set_drawable` for the meaning of `local_bounds`.
+    pub fn with_drawable<D: Drawable + 's>(drawable: D, local_bounds: FloatRect) -> SceneNode<'s> {
+        let mut node = SceneNode::new();
+        node.set_drawable(drawable, local_bounds);
+        node
+    }
+
+    /// Set what this node draws, replacing the previous drawable.
+    ///
+    /// `local_bounds` are the bounds of the drawable in the coordinates of the node, usually
+    /// the `local_bounds` of a sprite, text or shape. They are used to compute the bounds
+    /// of the node in the scene.
+    pub fn set_drawable<D: Drawable + 's>(&mut self, drawable: D, local_bounds: FloatRect) {
+        self.drawable = Some((Box::new(drawable), local_bounds));
+    }
+
+    /// Remove the drawable of this node, its children are kept.
+    pub fn clear_drawable(&mut self) {
+        self.drawable = None;
+    }
+
+    /// Tell whether this node has a drawable.
+    pub fn has_drawable(&self) -> bool {
+        self.drawable.is_some()
+    }
+
+    /// Set the name used to find this node with `find`, `world_transform` and `world_bounds`.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        self.name = name.into();
+    }
+
+    /// Return the name of this node, empty by default.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the drawing order of this node among its siblings.
+    ///
+    /// Nodes with a lower z-order are drawn first, i.e. below the others.
+    /// Nodes with a negative z-order are drawn below their parent's drawable.
+    /// The default z-order is 0.
+    pub fn set_z_order(&mut self, z_order: i32) {
+        self.z_order = z_order;
+    }
+
+    /// Return the drawing order of this node among its siblings.
+    pub fn z_order(&self) -> i32 {
+        self.z_order
+    }
+
+    /// Add a child to this node.
+    pub fn attach_child(&mut self, child: SceneNode<'s>) {
+        self.children.push(child);
+    }
+
+    /// Remove the child at `index` (in attachment order) and return it.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn detach_child(&mut self, index: usize) -> SceneNode<'s> {
+        self.children.remove(index)
+    }
+
+    /// Remove the first descendant named `name` from the tree and return it.
+    pub fn detach(&mut self, name: &str) -> Option<SceneNode<'s>> {
+        if let Some(index) = self.children.iter().position(|c| c.name == name) {
+            return Some(self.children.remove(index));
+        }
+        self.children.iter_mut().filter_map(|c| c.detach(name)).next()
+    }
+
+    /// Return the children of this node, in attachment order.
+    pub fn children(&self) -> &[SceneNode<'s>] {
+        &self.children
+    }
+
+    /// Return the children of this node, in attachment order.
+    pub fn children_mut(&mut self) -> &mut [SceneNode<'s>] {
+        &mut self.children
+    }
+
+    /// Return the first node named `name`, searching this node and its descendants
+    /// depth-first.
+    pub fn find(&self, name: &str) -> Option<&SceneNode<'s>> {
+        self.locate(name, Transform::identity()).map(|(node, _)| node)
+    }
+
+    /// Return the first node named `name`, searching this node and its descendants
+    /// depth-first.
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut SceneNode<'s>> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter_mut().filter_map(|c| c.find_mut(name)).next()
+    }
+
+    /// Return the combined transform of the node named `name`, from its own coordinates to the
+    /// coordinates this node is drawn in.
+    ///
+    /// Called on the root of a scene, this is the transform from the node to the world.
+    pub fn world_transform(&self, name: &str) -> Option<Transform> {
+        self.locate(name, Transform::identity()).map(|(node, parent)| parent * node.transform())
+    }
+
+    /// Return the bounds of the node named `name` and its descendants, in the coordinates
+    /// this node is drawn in.
+    ///
+    /// Returns `None` if there is no such node, or if neither it nor its descendants
+    /// have a drawable.
+    pub fn world_bounds(&self, name: &str) -> Option<FloatRect> {
+        self.locate(name, Transform::identity()).and_then(|(node, parent)| node.bounds_in(&parent))
+    }
+
+    /// Return the bounds of this node and its descendants, in the coordinates of its parent.
+    ///
+    /// Returns `None` if neither this node nor its descendants have a drawable.
+    pub fn global_bounds(&self) -> Option<FloatRect> {
+        self.bounds_in(&Transform::identity())
+    }
+
+    /// Find a node and the transform of its parent, relative to `parent`.
+    fn locate(&self, name: &str, parent: Transform) -> Option<(&SceneNode<'s>, Transform)> {
+        if self.name == name {
+            return Some((self, parent));
+        }
+        let transform = parent * self.transform();
+        self.children.iter().filter_map(|c| c.locate(name, transform)).next()
+    }
+
+    fn bounds_in(&self, parent: &Transform) -> Option<FloatRect> {
+        let transform = *parent * self.transform();
+        let own = self.drawable.as_ref().map(|d| transform.transform_rect(&d.1));
+        self.children
+            .iter()
+            .filter_map(|c| c.bounds_in(&transform))
+            .fold(own, |acc, b| Some(acc.map_or(b, |a| a.union(&b))))
+    }
+}
+
+impl<'s> Default for SceneNode<'s> {
+    fn default() -> Self {
+        SceneNode::new()
+    }
+}
+
+impl<'s> Drawable for SceneNode<'s> {
+    fn draw<'se, 'tex, 'sh, 'shte>(&'se self,
+                                   target: &mut dyn RenderTarget,
+                                   states: RenderStates<'tex, 'sh, 'shte>)
+        where 'se: 'sh
+    {
+        let transform = states.transform * self.transform();
+        let mut children: Vec<&'se SceneNode<'s>> = self.children.iter().collect();
+        children.sort_by_key(|c| c.z_order);
+        let below = children.iter().take_while(|c| c.z_order < 0).count();
+        let child_states = || {
+            RenderStates::new(states.blend_mode, transform, states.texture, states.shader)
+        };
+        for child in &children[..below] {
+            child.draw(target, child_states());
+        }
+        if let Some((ref drawable, _)) = self.drawable {
+            drawable.draw(target, child_states());
+        }
+        for child in &children[below..] {
+            child.draw(target, child_states());
+        }
+    }
+}
+
+impl<'s> Transformable for SceneNode<'s> {
+    fn set_position<P: Into<Vector2f>>(&mut self, position: P) {
+        self.transformable.set_position(position)
+    }
+    fn set_rotation(&mut self, angle: f32) {
+        self.transformable.set_rotation(angle)
+    }
+    fn set_scale<S: Into<Vector2f>>(&mut self, scale: S) {
+        self.transformable.set_scale(scale)
+    }
+    fn set_origin<O: Into<Vector2f>>(&mut self, origin: O) {
+        self.transformable.set_origin(origin)
+    }
+    fn position(&self) -> Vector2f {
+        self.transformable.position()
+    }
+    fn rotation(&self) -> f32 {
+        self.transformable.rotation()
+    }
+    fn get_scale(&self) -> Vector2f {
+        self.transformable.get_scale()
+    }
+    fn origin(&self) -> Vector2f {
+        self.transformable.origin()
+    }
+    fn move_<O: Into<Vector2f>>(&mut self, offset: O) {
+        self.transformable.move_(offset)
+    }
+    fn rotate(&mut self, angle: f32) {
+        self.transformable.rotate(angle)
+    }
+    fn scale<F: Into<Vector2f>>(&mut self, factors: F) {
+        self.transformable.scale(factors)
+    }
+    fn transform(&self) -> Transform {
+        self.transformable.transform()
+    }
+    fn inverse_transform(&self) -> Transform {
+        self.transformable.inverse_transform()
+    }
+}
+
+impl<'s> fmt::Debug for SceneNode<'s> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SceneNode")
+            .field("name", &self.name)
+            .field("transformable", &self.transformable)
+            .field("z_order", &self.z_order)
+            .field("bounds", &self.drawable.as_ref().map(|d| d.1))
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+#[test]
+fn world_transforms_and_bounds() {
+    struct Nothing;
+    impl Drawable for Nothing {
+        fn draw<'se, 'tex, 'sh, 'shte>(&'se self,
+                                       _: &mut dyn RenderTarget,
+                                       _: RenderStates<'tex, 'sh, 'shte>)
+            where 'se: 'sh
+        {
+        }
+    }
+
+    let mut root = SceneNode::new();
+    root.set_position((100., 0.));
+    root.set_scale((2., 2.));
+    let mut arm = SceneNode::with_drawable(Nothing, FloatRect::new(0., 0., 10., 1.));
+    arm.set_name("arm");
+    arm.set_rotation(-270.);
+    let mut hand = SceneNode::with_drawable(Nothing, FloatRect::new(-1., -1., 2., 2.));
+    hand.set_name("hand");
+    hand.set_position((10., 0.));
+    arm.attach_child(hand);
+    root.attach_child(arm);
+    assert_eq!(root.find("arm").unwrap().rotation(), 90.);
+
+    let hand = root.world_transform("hand").unwrap() * Vector2f::new(0., 0.);
+    assert!((hand.x - 100.).abs() < 1e-4 && (hand.y - 20.).abs() < 1e-4);
+    let bounds = root.world_bounds("arm").unwrap();
+    assert!((bounds.left - 98.).abs() < 1e-4 && (bounds.top + 0.).abs() < 1e-4);
+    assert!((bounds.width - 4.).abs() < 1e-4 && (bounds.height - 22.).abs() < 1e-4);
+    assert!(root.world_bounds("missing").is_none());
+
+    assert_eq!(root.detach("hand").unwrap().name(), "hand");
+    assert!(root.find("hand").is_none());
+    assert!(SceneNode::new().global_bounds().is_none());
+}
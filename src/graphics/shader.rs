@@ -1,3 +1,5 @@
+use assets::AssetSource;
+use error::{self, Error, ErrorKind};
 use graphics::{TextureRef, glsl};
 use graphics::csfml_graphics_sys as ffi;
 use inputstream::InputStream;
@@ -6,6 +8,7 @@ use std::ffi::CString;
 use std::io::{Read, Seek};
 use std::marker::PhantomData;
 use std::ptr;
+use system::err;
 use system::raw_conv::Raw;
 
 /// Shader type (vertex, geometry and fragment).
@@ -149,25 +152,30 @@ impl<'te> Shader<'te> {
     /// * fragmentShaderFilename - Some(Path) of the fragment shader file to load,
     ///                            or None to skip this shader
     ///
-    /// Return Some(Shader) or None
+    /// Return the new Shader, or an error if a file couldn't be read or the shader
+    /// failed to compile
     pub fn from_file(vertex: Option<&str>,
                      geometry: Option<&str>,
                      fragment: Option<&str>)
-                     -> Option<Shader<'te>> {
+                     -> error::Result<Shader<'te>> {
+        let paths: Vec<&str> = [vertex, geometry, fragment]
+            .iter()
+            .filter_map(|p| *p)
+            .collect();
+        for path in &paths {
+            error::check_readable(path)?;
+        }
         let cstring;
         let vert = cstring_then_ptr!(cstring, vertex);
         let cstring;
         let geom = cstring_then_ptr!(cstring, geometry);
         let cstring;
         let frag = cstring_then_ptr!(cstring, fragment);
-        let shader = unsafe { ffi::sfShader_createFromFile(vert, geom, frag) };
-        if shader.is_null() {
-            None
-        } else {
-            Some(Shader {
-                     shader: shader,
-                     texture: PhantomData,
-                 })
+        let created = err::capture("Shader::from_file",
+                                   || unsafe { ffi::sfShader_createFromFile(vert, geom, frag) });
+        match Shader::from_created(created) {
+            Err(e) if paths.len() == 1 => Err(e.with_path(paths[0])),
+            result => result,
         }
     }
 
@@ -187,30 +195,39 @@ impl<'te> Shader<'te> {
     /// * fragmentShaderStream - Some(T: Read + Seek) of the fragment shader stream to load,
     ///                          or None to skip this shader
     ///
-    /// Return Some(Shader) or None
+    /// Return the new Shader, or an error if a stream failed or the shader failed to compile
     pub fn from_stream<T: Read + Seek>(vertex_shader_stream: Option<&mut T>,
                                        geometry_shader_stream: Option<&mut T>,
                                        fragment_shader_stream: Option<&mut T>)
-                                       -> Option<Shader<'te>> {
+                                       -> error::Result<Shader<'te>> {
         let mut vertex_stream = vertex_shader_stream.map(InputStream::new);
         let mut geometry_stream = geometry_shader_stream.map(InputStream::new);
         let mut fragment_stream = fragment_shader_stream.map(InputStream::new);
-        let vertex_ptr = vertex_stream.as_mut().map_or(ptr::null_mut(), |s| &mut s.0);
+        let vertex_ptr = vertex_stream
+            .as_mut()
+            .map_or(ptr::null_mut(), InputStream::raw_mut);
         let geometry_ptr = geometry_stream
             .as_mut()
-            .map_or(ptr::null_mut(), |s| &mut s.0);
+            .map_or(ptr::null_mut(), InputStream::raw_mut);
         let fragment_ptr = fragment_stream
             .as_mut()
-            .map_or(ptr::null_mut(), |s| &mut s.0);
-        let shader =
-            unsafe { ffi::sfShader_createFromStream(vertex_ptr, geometry_ptr, fragment_ptr) };
-        if shader.is_null() {
-            None
-        } else {
-            Some(Shader {
-                     shader: shader,
-                     texture: PhantomData,
-                 })
+            .map_or(ptr::null_mut(), InputStream::raw_mut);
+        let created = err::capture("Shader::from_stream", || unsafe {
+            ffi::sfShader_createFromStream(vertex_ptr, geometry_ptr, fragment_ptr)
+        });
+        let io_error = vertex_stream
+            .as_mut()
+            .and_then(InputStream::finish)
+            .or_else(|| geometry_stream.as_mut().and_then(InputStream::finish))
+            .or_else(|| fragment_stream.as_mut().and_then(InputStream::finish));
+        match Shader::from_created(created) {
+            Err(e) => {
+                match io_error {
+                    Some(io_error) => Err(Error::from(io_error).with_message(e.message())),
+                    None => Err(e),
+                }
+            }
+            shader => shader,
         }
     }
 
@@ -230,25 +247,63 @@ impl<'te> Shader<'te> {
     /// * fragmentShader - Some(String) containing the source code of the fragment shader,
     ///                    or None to skip this shader
     ///
-    /// Return Some(Shader) or None
+    /// Return the new Shader, or an error if the shader failed to compile
     pub fn from_memory(vertex: Option<&str>,
                        geometry: Option<&str>,
                        fragment: Option<&str>)
-                       -> Option<Shader<'te>> {
+                       -> error::Result<Shader<'te>> {
         let cstring;
         let vert = cstring_then_ptr!(cstring, vertex);
         let cstring;
         let geom = cstring_then_ptr!(cstring, geometry);
         let cstring;
         let frag = cstring_then_ptr!(cstring, fragment);
-        let shader = unsafe { ffi::sfShader_createFromMemory(vert, geom, frag) };
-        if shader.is_null() {
-            None
+        let created = err::capture("Shader::from_memory",
+                                   || unsafe { ffi::sfShader_createFromMemory(vert, geom, frag) });
+        Shader::from_created(created)
+    }
+
+    /// Load the vertex, geometry and fragment shaders from assets
+    ///
+    /// # Arguments
+    /// * source - The directory or archive containing the shader sources
+    /// * vertex - Logical path of the vertex shader source, or None to skip this shader
+    /// * geometry - Logical path of the geometry shader source, or None to skip this shader
+    /// * fragment - Logical path of the fragment shader source, or None to skip this shader
+    ///
+    /// Return the new Shader, or an error if an asset couldn't be read or the shader failed
+    /// to compile
+    pub fn from_asset<S: AssetSource + ?Sized>(source: &S,
+                                               vertex: Option<&str>,
+                                               geometry: Option<&str>,
+                                               fragment: Option<&str>)
+                                               -> error::Result<Shader<'te>> {
+        let read = |path: Option<&str>| path.map(|p| source.read_to_string(p)).transpose();
+        let vertex_source = read(vertex)?;
+        let geometry_source = read(geometry)?;
+        let fragment_source = read(fragment)?;
+        let result = Shader::from_memory(vertex_source.as_ref().map(|s| &s[..]),
+                                         geometry_source.as_ref().map(|s| &s[..]),
+                                         fragment_source.as_ref().map(|s| &s[..]));
+        let paths: Vec<&str> = vertex.iter().chain(&geometry).chain(&fragment).cloned().collect();
+        match result {
+            Err(e) if paths.len() == 1 => Err(e.with_path(paths[0])),
+            result => result,
+        }
+    }
+
+    fn from_created((shader, message): (*mut ffi::sfShader, String))
+                    -> error::Result<Shader<'te>> {
+        if !shader.is_null() {
+            Ok(Shader {
+                   shader: shader,
+                   texture: PhantomData,
+               })
+        } else if !Shader::is_available() {
+            Err(Error::new(ErrorKind::ResourceCreation)
+                    .with_message("shaders are not supported by the graphics driver"))
         } else {
-            Some(Shader {
-                     shader: shader,
-                     texture: PhantomData,
-                 })
+            Err(Error::new(ErrorKind::DecodeFailed).with_message(message))
         }
     }
 
@@ -0,0 +1,423 @@
+use graphics::{BlendMode, CircleShape, Color, ConvexShape, CustomShape, Drawable, FloatRect,
+               Glyph, Image, IntRect, PrimitiveType, RectangleShape, RenderStates, RenderTarget,
+               Shape, Sprite, Text, TextureRef, Transform, Transformable, Vertex, VertexArray,
+               View, ViewRef};
+use graphics::csfml_graphics_sys as ffi;
+use graphics::rasterizer::{self, Canvas, DrawState, Texels};
+use graphics::text_style;
+use std::cell::RefCell;
+use std::fmt;
+use system::{Vector2f, Vector2i, Vector2u};
+use system::raw_conv::Raw;
+
+/// A render target that draws on the CPU, into an image.
+///
+/// It renders sprites, texts, shapes, vertex arrays and primitives like the GPU targets do,
+/// with the transform, texture and blend mode of the `RenderStates` and the current view,
+/// so that rendering code can be tested on machines without a GPU and compared with
+/// reference images (see `to_image`).
+///
+/// The rendering is close to SFML's but not identical, it has the following limitations:
+///
+/// - there is no antialiasing, and textures are sampled with the nearest pixel, even if
+///   they are smooth;
+/// - shaders are ignored;
+/// - the outlines of texts aren't drawn.
+///
+/// Only untextured geometry is drawn without OpenGL: shapes, vertex arrays and primitives
+/// drawn without a texture. Textures live on the GPU, so drawing a sprite, a text or a
+/// textured shape reads its texture back, which needs an OpenGL context (software
+/// implementations such as Mesa's llvmpipe are fine).
+///
+/// The pixels of a texture are read again for every draw, so updates to textures are always
+/// taken into account, at the cost of speed.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{CircleShape, Color, RenderTarget, Shape, SoftwareRenderTarget,
+///                      Transformable};
+///
+/// let mut target = SoftwareRenderTarget::new(64, 64);
+/// target.clear(&Color::black());
+/// let mut circle = CircleShape::new(16., 30);
+/// circle.set_fill_color(&Color::red());
+/// circle.set_position((16., 16.));
+/// target.draw(&circle);
+///
+/// assert_eq!(target.pixel_at(32, 32), Color::red());
+/// target.to_image().save_to_file("circle.png").unwrap();
+/// ```
+pub struct SoftwareRenderTarget {
+    canvas: RefCell<Canvas>,
+    view: View,
+    default_view: View,
+}
+
+impl SoftwareRenderTarget {
+    /// Create a render target of the given size, filled with transparent black.
+    pub fn new(width: u32, height: u32) -> SoftwareRenderTarget {
+        let default_view = View::from_rect(&FloatRect::new(0., 0., width as f32, height as f32));
+        SoftwareRenderTarget {
+            canvas: RefCell::new(Canvas::new(width, height, Color::transparent())),
+            view: default_view.clone(),
+            default_view,
+        }
+    }
+
+    /// Return the color of a pixel.
+    ///
+    /// # Panics
+    /// Panics if the pixel is out of the target.
+    pub fn pixel_at(&self, x: u32, y: u32) -> Color {
+        let canvas = self.canvas.borrow();
+        assert!(x < canvas.width && y < canvas.height,
+                "Out of bounds: ({}, {}), size ({}, {})",
+                x,
+                y,
+                canvas.width,
+                canvas.height);
+        canvas.pixel(x, y)
+    }
+
+    /// Return the pixels of the target, as RGBA bytes row by row.
+    pub fn pixel_data(&self) -> Vec<u8> {
+        let canvas = self.canvas.borrow();
+        let mut data = Vec::with_capacity(canvas.pixels.len() * 4);
+        for pixel in &canvas.pixels {
+            data.extend_from_slice(&[pixel.r, pixel.g, pixel.b, pixel.a]);
+        }
+        data
+    }
+
+    /// Copy the content of the target to an image.
+    pub fn to_image(&self) -> Image {
+        let size = self.size();
+        Image::create_from_pixels(size.x, size.y, &self.pixel_data())
+            .expect("Not enough memory to create the Image")
+    }
+
+    fn viewport_of(&self, view: &ViewRef) -> IntRect {
+        let size = self.size();
+        let (width, height) = (size.x as f32, size.y as f32);
+        let viewport = view.viewport();
+        IntRect::new((0.5 + width * viewport.left) as i32,
+                     (0.5 + height * viewport.top) as i32,
+                     (0.5 + width * viewport.width) as i32,
+                     (0.5 + height * viewport.height) as i32)
+    }
+
+    /// Return the transform from the coordinates of `view` to the pixels of the target.
+    fn view_to_pixels(&self, view: &ViewRef) -> Transform {
+        let viewport = self.viewport_of(view);
+        let (center, size) = (view.center(), view.size());
+        Transform::identity()
+            .translated(viewport.left as f32 + viewport.width as f32 / 2.,
+                        viewport.top as f32 + viewport.height as f32 / 2.)
+            .scaled(viewport.width as f32 / size.x,
+                    viewport.height as f32 / size.y)
+            .rotated(-view.rotation())
+            .translated(-center.x, -center.y)
+    }
+
+    fn render(&self,
+              vertices: &[Vertex],
+              primitive_type: PrimitiveType,
+              transform: Transform,
+              blend_mode: BlendMode,
+              texture: Option<&TextureRef>) {
+        if vertices.is_empty() {
+            return;
+        }
+        let texels = texture.and_then(read_texels);
+        let state = DrawState {
+            transform: self.view_to_pixels(&self.view) * transform,
+            clip: self.viewport_of(&self.view),
+            texture: texels.as_ref(),
+            blend_mode,
+        };
+        self.canvas.borrow_mut().draw(vertices, primitive_type, &state);
+    }
+
+    fn render_shape<'s, S: Shape<'s>>(&self, shape: &S, states: RenderStates) {
+        let points: Vec<Vector2f> = (0..shape.point_count()).map(|i| shape.point(i)).collect();
+        let transform = states.transform * shape.transform();
+        let fill = rasterizer::shape_fill(&points, shape.fill_color(), shape.texture_rect());
+        self.render(&fill,
+                    PrimitiveType::TriangleFan,
+                    transform,
+                    states.blend_mode,
+                    shape.texture());
+        let outline = rasterizer::shape_outline(&points,
+                                                shape.outline_thickness(),
+                                                shape.outline_color());
+        self.render(&outline,
+                    PrimitiveType::TriangleStrip,
+                    transform,
+                    states.blend_mode,
+                    None);
+    }
+}
+
+/// Copy the pixels of a texture.
+fn read_texels(texture: &TextureRef) -> Option<Texels> {
+    let image = texture.copy_to_image()?;
+    let size = image.size();
+    Some(Texels {
+        width: size.x,
+        height: size.y,
+        pixels: image.pixel_data().chunks(4).map(|p| Color::rgba(p[0], p[1], p[2], p[3])).collect(),
+        repeated: texture.is_repeated(),
+    })
+}
+
+/// Add the two triangles of a glyph, laid out as `sf::Text` does.
+fn glyph_quad(vertices: &mut Vec<Vertex>, x: f32, y: f32, color: Color, glyph: &Glyph, shear: f32) {
+    let (left, top) = (glyph.bounds.left, glyph.bounds.top);
+    let (right, bottom) = (left + glyph.bounds.width, top + glyph.bounds.height);
+    let rect = glyph.texture_rect;
+    let (u1, v1) = (rect.left as f32, rect.top as f32);
+    let (u2, v2) = (u1 + rect.width as f32, v1 + rect.height as f32);
+    let top_left = Vertex::new((x + left - shear * top, y + top), color, Vector2f::new(u1, v1));
+    let top_right = Vertex::new((x + right - shear * top, y + top), color, Vector2f::new(u2, v1));
+    let bottom_left =
+        Vertex::new((x + left - shear * bottom, y + bottom), color, Vector2f::new(u1, v2));
+    let bottom_right =
+        Vertex::new((x + right - shear * bottom, y + bottom), color, Vector2f::new(u2, v2));
+    vertices.extend_from_slice(&[top_left, top_right, bottom_left, bottom_left, top_right,
+                                 bottom_right]);
+}
+
+/// Add the two triangles of an underline, textured with the white square at the top-left
+/// of font textures.
+fn underline(vertices: &mut Vec<Vertex>, length: f32, y: f32, color: Color, offset: f32,
+             thickness: f32) {
+    let top = (y + offset - thickness / 2. + 0.5).floor();
+    let bottom = top + (thickness + 0.5).floor();
+    let white = Vector2f::new(1., 1.);
+    let corners = [(0., top), (length, top), (0., bottom), (0., bottom), (length, top),
+                   (length, bottom)];
+    vertices.extend(corners.iter().map(|&position| Vertex::new(position, color, white)));
+}
+
+impl RenderTarget for SoftwareRenderTarget {
+    fn clear(&mut self, color: &Color) {
+        self.canvas.borrow_mut().clear(*color)
+    }
+
+    fn view(&self) -> &ViewRef {
+        &self.view
+    }
+
+    fn default_view(&self) -> &ViewRef {
+        &self.default_view
+    }
+
+    fn set_view(&mut self, view: &View) {
+        self.view = view.clone();
+    }
+
+    fn viewport(&self, view: &View) -> IntRect {
+        self.viewport_of(view)
+    }
+
+    fn map_pixel_to_coords(&self, point: &Vector2i, view: &View) -> Vector2f {
+        self.view_to_pixels(view).inverse() * Vector2f::new(point.x as f32, point.y as f32)
+    }
+
+    fn map_pixel_to_coords_current_view(&self, point: &Vector2i) -> Vector2f {
+        self.view_to_pixels(&self.view).inverse() * Vector2f::new(point.x as f32, point.y as f32)
+    }
+
+    fn map_coords_to_pixel(&self, point: &Vector2f, view: &View) -> Vector2i {
+        let pixel = self.view_to_pixels(view) * *point;
+        Vector2i::new(pixel.x as i32, pixel.y as i32)
+    }
+
+    fn map_coords_to_pixel_current_view(&self, point: &Vector2f) -> Vector2i {
+        let pixel = self.view_to_pixels(&self.view) * *point;
+        Vector2i::new(pixel.x as i32, pixel.y as i32)
+    }
+
+    fn draw(&mut self, object: &dyn Drawable) {
+        object.draw(self, RenderStates::default());
+    }
+
+    fn draw_with_renderstates(&mut self, object: &dyn Drawable, render_states: RenderStates) {
+        object.draw(self, render_states);
+    }
+
+    fn size(&self) -> Vector2u {
+        let canvas = self.canvas.borrow();
+        Vector2u::new(canvas.width, canvas.height)
+    }
+
+    /// Does nothing, there are no OpenGL states.
+    fn push_gl_states(&mut self) {}
+
+    /// Does nothing, there are no OpenGL states.
+    fn pop_gl_states(&mut self) {}
+
+    /// Does nothing, there are no OpenGL states.
+    fn reset_gl_states(&mut self) {}
+
+    /// Draw the fill of the text, and its underline. Outlines aren't supported.
+    fn draw_text(&self, text: &Text, rs: RenderStates) {
+        let font = match text.font() {
+            Some(font) => font,
+            None => return,
+        };
+        let size = text.character_size();
+        let style = text.style();
+        let bold = style.contains(text_style::BOLD);
+        // 12 degrees, as sf::Text
+        let shear = if style.contains(text_style::ITALIC) { 0.209 } else { 0. };
+        let underlined = style.contains(text_style::UNDERLINED);
+        let underline_offset = font.underline_position(size);
+        let underline_thickness = font.underline_thickness(size);
+        let color = text.fill_color();
+        let whitespace = font.glyph(' ' as u32, size, bold, 0.).advance;
+        let line_spacing = font.line_spacing(size) as f32;
+
+        let mut vertices = Vec::new();
+        let (mut x, mut y) = (0., size as f32);
+        let mut previous = 0;
+        for c in text.string().chars() {
+            let codepoint = c as u32;
+            x += font.kerning(previous, codepoint, size) as f32;
+            previous = codepoint;
+            if underlined && c == '\n' {
+                underline(&mut vertices, x, y, color, underline_offset, underline_thickness);
+            }
+            match c {
+                ' ' => x += whitespace,
+                '\t' => x += whitespace * 4.,
+                '\n' => {
+                    y += line_spacing;
+                    x = 0.;
+                }
+                _ => {
+                    let glyph = font.glyph(codepoint, size, bold, 0.);
+                    glyph_quad(&mut vertices, x, y, color, &glyph, shear);
+                    x += glyph.advance;
+                }
+            }
+        }
+        if underlined && x > 0. {
+            underline(&mut vertices, x, y, color, underline_offset, underline_thickness);
+        }
+
+        // Get the texture after the glyphs, loading them may have resized it.
+        // CSFML takes a mutable font, but sf::Font::getTexture is const.
+        let texture = unsafe { ffi::sfFont_getTexture(font.raw() as *mut _, size) };
+        assert!(!texture.is_null(), "sfFont_getTexture failed");
+        let texture = unsafe { &*(texture as *const TextureRef) };
+        self.render(&vertices,
+                    PrimitiveType::Triangles,
+                    rs.transform * text.transform(),
+                    rs.blend_mode,
+                    Some(texture));
+    }
+
+    fn draw_shape(&self, shape: &CustomShape, rs: RenderStates) {
+        self.render_shape(shape, rs)
+    }
+
+    /// Draw the sprite, sprites without a texture are not drawn.
+    fn draw_sprite(&self, sprite: &Sprite, rs: RenderStates) {
+        let texture = match sprite.texture() {
+            Some(texture) => texture,
+            None => return,
+        };
+        let rect = sprite.texture_rect();
+        let (width, height) = (rect.width.abs() as f32, rect.height.abs() as f32);
+        let (left, top) = (rect.left as f32, rect.top as f32);
+        let (right, bottom) = (left + rect.width as f32, top + rect.height as f32);
+        let color = sprite.color();
+        let vertices = [Vertex::new((0., 0.), color, Vector2f::new(left, top)),
+                        Vertex::new((0., height), color, Vector2f::new(left, bottom)),
+                        Vertex::new((width, 0.), color, Vector2f::new(right, top)),
+                        Vertex::new((width, height), color, Vector2f::new(right, bottom))];
+        self.render(&vertices,
+                    PrimitiveType::TriangleStrip,
+                    rs.transform * sprite.transform(),
+                    rs.blend_mode,
+                    Some(texture));
+    }
+
+    fn draw_circle_shape(&self, circle_shape: &CircleShape, rs: RenderStates) {
+        self.render_shape(circle_shape, rs)
+    }
+
+    fn draw_rectangle_shape(&self, rectangle_shape: &RectangleShape, rs: RenderStates) {
+        self.render_shape(rectangle_shape, rs)
+    }
+
+    fn draw_convex_shape(&self, convex_shape: &ConvexShape, rs: RenderStates) {
+        self.render_shape(convex_shape, rs)
+    }
+
+    fn draw_vertex_array(&self, vertex_array: &VertexArray, rs: RenderStates) {
+        let vertices: Vec<Vertex> =
+            (0..vertex_array.vertex_count()).map(|i| vertex_array[i]).collect();
+        self.draw_primitives(&vertices, vertex_array.primitive_type(), rs)
+    }
+
+    fn draw_primitives(&self, vertices: &[Vertex], ty: PrimitiveType, rs: RenderStates) {
+        self.render(vertices, ty, rs.transform, rs.blend_mode, rs.texture)
+    }
+}
+
+impl fmt::Debug for SoftwareRenderTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SoftwareRenderTarget")
+            .field("size", &self.size())
+            .field("view", &self.view)
+            .finish()
+    }
+}
+
+#[test]
+fn headless_rendering() {
+    use graphics::blend_mode;
+
+    let square = |left: f32, top: f32, right: f32, bottom: f32, color: Color| {
+        [Vertex::with_pos_color((left, top), color),
+         Vertex::with_pos_color((right, top), color),
+         Vertex::with_pos_color((right, bottom), color),
+         Vertex::with_pos_color((left, bottom), color)]
+    };
+    let mut target = SoftwareRenderTarget::new(64, 64);
+    assert_eq!(target.pixel_at(10, 10), Color::transparent());
+    target.clear(&Color::black());
+    target.draw_primitives(&square(2., 2., 6., 6., Color::red()),
+                           PrimitiveType::Quads,
+                           RenderStates::default());
+    let half_blue = Color::rgba(0, 0, 255, 128);
+    target.draw_primitives(&square(4., 4., 10., 10., half_blue),
+                           PrimitiveType::Quads,
+                           RenderStates::default());
+    assert_eq!(target.pixel_at(3, 3), Color::red());
+    assert_eq!(target.pixel_at(5, 5), blend_mode::ALPHA.blend(half_blue, Color::red()));
+    assert_eq!(target.pixel_at(8, 8), blend_mode::ALPHA.blend(half_blue, Color::black()));
+    assert_eq!(target.pixel_at(1, 1), Color::black());
+    assert_eq!(target.pixel_at(12, 12), Color::black());
+    let image = target.to_image();
+    assert_eq!(image.size(), Vector2u::new(64, 64));
+    assert_eq!(image.pixel_at(3, 3), Color::red());
+    assert_eq!(&target.pixel_data()[..4], &[0, 0, 0, 255]);
+
+    // A 32x32 view shown in the bottom-right quarter, drawing outside of it is clipped
+    let mut view = View::from_rect(&FloatRect::new(0., 0., 32., 32.));
+    view.set_viewport(&FloatRect::new(0.5, 0.5, 0.5, 0.5));
+    let mut target = SoftwareRenderTarget::new(64, 64);
+    target.set_view(&view);
+    target.draw_primitives(&square(-10., -10., 4., 4., Color::green()),
+                           PrimitiveType::Quads,
+                           RenderStates::default());
+    assert_eq!(target.map_coords_to_pixel_current_view(&Vector2f::new(4., 4.)),
+               Vector2i::new(36, 36));
+    assert_eq!(target.pixel_at(34, 34), Color::green());
+    assert_eq!(target.pixel_at(30, 30), Color::transparent());
+    assert_eq!(target.pixel_at(37, 37), Color::transparent());
+}
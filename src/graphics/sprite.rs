@@ -216,10 +216,10 @@ impl<'s> Transformable for Sprite<'s> {
         unsafe { ffi::sfSprite_scale(self.sprite, factors.into().raw()) }
     }
     fn transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfSprite_getTransform(self.sprite)) }
+        unsafe { Transform::from_raw(ffi::sfSprite_getTransform(self.sprite)) }
     }
     fn inverse_transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfSprite_getInverseTransform(self.sprite)) }
+        unsafe { Transform::from_raw(ffi::sfSprite_getInverseTransform(self.sprite)) }
     }
 }
 
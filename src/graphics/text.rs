@@ -284,10 +284,10 @@ impl<'s> Transformable for Text<'s> {
         unsafe { ffi::sfText_scale(self.text, factors.into().raw()) }
     }
     fn transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfText_getTransform(self.text)) }
+        unsafe { Transform::from_raw(ffi::sfText_getTransform(self.text)) }
     }
     fn inverse_transform(&self) -> Transform {
-        unsafe { Transform(ffi::sfText_getInverseTransform(self.text)) }
+        unsafe { Transform::from_raw(ffi::sfText_getInverseTransform(self.text)) }
     }
 }
 
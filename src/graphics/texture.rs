@@ -1,14 +1,15 @@
+use assets::AssetSource;
 use csfml_system_sys::sfBool;
+use error::{self, Error, ErrorKind};
 use graphics::{Image, IntRect, RenderWindow};
 use graphics::csfml_graphics_sys as ffi;
-use inputstream::InputStream;
 use sf_bool_ext::SfBoolExt;
 use std::borrow::{Borrow, ToOwned};
-use std::ffi::CString;
 use std::io::{Read, Seek};
 use std::ops::Deref;
 use std::ptr;
 use system::Vector2u;
+use system::err;
 use system::raw_conv::{FromRaw, Raw};
 use window::Window;
 
@@ -92,13 +93,14 @@ impl Texture {
     /// * width - Texture width
     /// * height - Texture height
     ///
-    /// Return Some(Texture) or None
-    pub fn new(width: u32, height: u32) -> Option<Texture> {
-        let tex = unsafe { ffi::sfTexture_create(width, height) };
+    /// Return the new Texture, or an error if it couldn't be allocated
+    pub fn new(width: u32, height: u32) -> error::Result<Texture> {
+        let (tex, message) = err::capture("Texture::new",
+                                          || unsafe { ffi::sfTexture_create(width, height) });
         if tex.is_null() {
-            None
+            Err(Error::new(ErrorKind::ResourceCreation).with_message(message))
         } else {
-            Some(Texture { texture: tex })
+            Ok(Texture { texture: tex })
         }
     }
 
@@ -108,16 +110,10 @@ impl Texture {
     /// * mem - Pointer to the file data in memory
     /// * area - Area of the image to load
     ///
-    /// Return Some(Texture) or None
-    pub fn from_memory(mem: &[u8], area: &IntRect) -> Option<Texture> {
-        let tex = unsafe {
-            ffi::sfTexture_createFromMemory(mem.as_ptr() as *const _, mem.len(), &area.raw())
-        };
-        if tex.is_null() {
-            None
-        } else {
-            Some(Texture { texture: tex })
-        }
+    /// Return the new Texture, or an error if the data couldn't be decoded or uploaded
+    pub fn from_memory(mem: &[u8], area: &IntRect) -> error::Result<Texture> {
+        let image = Image::from_memory(mem)?;
+        Texture::from_image_with_rect(&image, area)
     }
 
     /// Create a new texture from a stream (a struct implementing Read + Seek)
@@ -125,15 +121,12 @@ impl Texture {
     /// # Arguments
     /// * stream - Your struct, implementing Read and Seek
     ///
-    /// Return Some(Texture) or None
-    pub fn from_stream<T: Read + Seek>(stream: &mut T, area: &mut IntRect) -> Option<Texture> {
-        let mut input_stream = InputStream::new(stream);
-        let tex = unsafe { ffi::sfTexture_createFromStream(&mut input_stream.0, &area.raw()) };
-        if tex.is_null() {
-            None
-        } else {
-            Some(Texture { texture: tex })
-        }
+    /// Return the new Texture, or an error if the data couldn't be decoded or uploaded
+    pub fn from_stream<T: Read + Seek>(stream: &mut T,
+                                       area: &mut IntRect)
+                                       -> error::Result<Texture> {
+        let image = Image::from_stream(stream)?;
+        Texture::from_image_with_rect(&image, area)
     }
 
     /// Create a new texture from a file
@@ -141,15 +134,22 @@ impl Texture {
     /// # Arguments
     /// * filename - Path of the image file to load
     ///
-    /// Return Some(Texture) or None
-    pub fn from_file(filename: &str) -> Option<Texture> {
-        let c_str = CString::new(filename.as_bytes()).unwrap();
-        let tex = unsafe { ffi::sfTexture_createFromFile(c_str.as_ptr(), ptr::null()) };
-        if tex.is_null() {
-            None
-        } else {
-            Some(Texture { texture: tex })
-        }
+    /// Return the new Texture, or an error describing why the file couldn't be loaded
+    pub fn from_file(filename: &str) -> error::Result<Texture> {
+        let image = Image::from_file(filename)?;
+        Texture::from_image(&image).map_err(|e| e.with_path(filename))
+    }
+
+    /// Create a new texture from an asset
+    ///
+    /// # Arguments
+    /// * source - The directory or archive containing the asset
+    /// * path - Logical path of the image in `source`
+    ///
+    /// Return the new Texture, or an error describing why the asset couldn't be loaded
+    pub fn from_asset<S: AssetSource + ?Sized>(source: &S, path: &str) -> error::Result<Texture> {
+        let image = Image::from_asset(source, path)?;
+        Texture::from_image(&image).map_err(|e| e.with_path(path))
     }
 
     /// Create a new texture from a file with a given area
@@ -158,15 +158,10 @@ impl Texture {
     /// * filename - Path of the image file to load
     /// * area - Area of the source image to load
     ///
-    /// Return Some(Texture) or None
-    pub fn from_file_with_rect(filename: &str, area: &IntRect) -> Option<Texture> {
-        let c_str = CString::new(filename.as_bytes()).unwrap();
-        let tex = unsafe { ffi::sfTexture_createFromFile(c_str.as_ptr(), &area.raw()) };
-        if tex.is_null() {
-            None
-        } else {
-            Some(Texture { texture: tex })
-        }
+    /// Return the new Texture, or an error describing why the file couldn't be loaded
+    pub fn from_file_with_rect(filename: &str, area: &IntRect) -> error::Result<Texture> {
+        let image = Image::from_file(filename)?;
+        Texture::from_image_with_rect(&image, area).map_err(|e| e.with_path(filename))
     }
 
     /// Create a new texture from an image
@@ -175,13 +170,15 @@ impl Texture {
     /// * image - Image to upload to the texture
     /// * area - Area of the source image to load
     ///
-    /// Return Some(Texture) or None
-    pub fn from_image_with_rect(image: &Image, area: &IntRect) -> Option<Texture> {
-        let tex = unsafe { ffi::sfTexture_createFromImage(image.raw(), &area.raw()) };
+    /// Return the new Texture, or an error if it couldn't be allocated
+    pub fn from_image_with_rect(image: &Image, area: &IntRect) -> error::Result<Texture> {
+        let (tex, message) = err::capture("Texture::from_image_with_rect", || unsafe {
+            ffi::sfTexture_createFromImage(image.raw(), &area.raw())
+        });
         if tex.is_null() {
-            None
+            Err(Error::new(ErrorKind::ResourceCreation).with_message(message))
         } else {
-            Some(Texture { texture: tex })
+            Ok(Texture { texture: tex })
         }
     }
 
@@ -190,13 +187,15 @@ impl Texture {
     /// # Arguments
     /// * image - Image to upload to the texture
     ///
-    /// Return Some(Texture) or None
-    pub fn from_image(image: &Image) -> Option<Texture> {
-        let tex = unsafe { ffi::sfTexture_createFromImage(image.raw(), ptr::null()) };
+    /// Return the new Texture, or an error if it couldn't be allocated
+    pub fn from_image(image: &Image) -> error::Result<Texture> {
+        let (tex, message) = err::capture("Texture::from_image", || unsafe {
+            ffi::sfTexture_createFromImage(image.raw(), ptr::null())
+        });
         if tex.is_null() {
-            None
+            Err(Error::new(ErrorKind::ResourceCreation).with_message(message))
         } else {
-            Some(Texture { texture: tex })
+            Ok(Texture { texture: tex })
         }
     }
 
@@ -0,0 +1,401 @@
+use error::{self, Error, ErrorKind};
+use graphics::{Color, Image, IntRect, Texture};
+use std::cmp;
+use std::collections::HashMap;
+use system::Vector2u;
+
+/// Packs many small images into a single image, to draw them all from one texture.
+///
+/// Drawing sprites that share a texture is faster than switching textures, and a single
+/// large texture uses less memory than many small ones.
+///
+/// The images are packed with the MaxRects algorithm (see `pack_rects`), `padding`
+/// transparent pixels apart. To avoid bleeding of the neighbouring pixels when the sprites
+/// are scaled or drawn at fractional positions, the edges of each image can also be
+/// extruded: its outermost pixels are repeated around it, in the padding.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{Image, Sprite, TextureAtlas};
+///
+/// let mut atlas = TextureAtlas::new();
+/// atlas.set_padding(2);
+/// atlas.set_extrusion(1);
+/// for name in &["player", "enemy", "coin"] {
+///     atlas.add_image(*name, Image::from_file(&format!("{}.png", name)).unwrap());
+/// }
+/// let packed = atlas.build().unwrap();
+/// let texture = packed.texture().unwrap();
+///
+/// let mut coin = Sprite::with_texture(&texture);
+/// coin.set_texture_rect(&packed.rect("coin").unwrap());
+/// ```
+#[derive(Debug)]
+pub struct TextureAtlas {
+    images: Vec<(String, Image)>,
+    padding: u32,
+    extrusion: u32,
+    max_size: u32,
+}
+
+impl TextureAtlas {
+    /// Create an empty atlas, with a padding of 2 pixels, an extrusion of 1 pixel and a
+    /// maximum size of 4096 pixels.
+    pub fn new() -> TextureAtlas {
+        TextureAtlas {
+            images: Vec::new(),
+            padding: 2,
+            extrusion: 1,
+            max_size: 4096,
+        }
+    }
+
+    /// Add an image to pack, replacing the image with the same name if any.
+    pub fn add_image<N: Into<String>>(&mut self, name: N, image: Image) {
+        let name = name.into();
+        match self.images.iter_mut().find(|entry| entry.0 == name) {
+            Some(entry) => entry.1 = image,
+            None => self.images.push((name, image)),
+        }
+    }
+
+    /// Return the number of images to pack.
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    /// Tell whether there are no images to pack.
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    /// Set the number of pixels between the images and around them, extruded pixels
+    /// excluded.
+    pub fn set_padding(&mut self, padding: u32) {
+        self.padding = padding;
+    }
+
+    /// Return the number of pixels between the images.
+    pub fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    /// Set how many times the edge pixels of the images are repeated around them.
+    pub fn set_extrusion(&mut self, extrusion: u32) {
+        self.extrusion = extrusion;
+    }
+
+    /// Return how many times the edge pixels of the images are repeated around them.
+    pub fn extrusion(&self) -> u32 {
+        self.extrusion
+    }
+
+    /// Set the maximum width and height of the atlas.
+    ///
+    /// It should not exceed `Texture::maximum_size()`, which is at least 4096 on current
+    /// hardware.
+    pub fn set_max_size(&mut self, max_size: u32) {
+        self.max_size = max_size;
+    }
+
+    /// Return the maximum width and height of the atlas.
+    pub fn max_size(&self) -> u32 {
+        self.max_size
+    }
+
+    /// Pack the images into a single image.
+    ///
+    /// Fails with `ErrorKind::ResourceCreation` if the images don't fit in the maximum size.
+    pub fn build(&self) -> error::Result<PackedAtlas> {
+        let sizes: Vec<Vector2u> = self.images.iter().map(|entry| entry.1.size()).collect();
+        let packed = pack_rects(&sizes, self.padding, self.extrusion, self.max_size);
+        let (size, rects) = match packed {
+            Some(packed) => packed,
+            None => {
+                let message = format!("{} images don't fit in {}x{} pixels",
+                                      self.images.len(),
+                                      self.max_size,
+                                      self.max_size);
+                return Err(Error::new(ErrorKind::ResourceCreation).with_message(message));
+            }
+        };
+
+        let mut image = Image::from_color(size.x, size.y, &Color::transparent())?;
+        for ((_, source), rect) in self.images.iter().zip(&rects) {
+            image.copy_image(source,
+                             rect.left as u32,
+                             rect.top as u32,
+                             &IntRect::new(0, 0, 0, 0),
+                             false);
+            extrude(image.pixels_mut(), size.x, rect, self.extrusion);
+        }
+        let names = self.images.iter().map(|entry| entry.0.clone());
+        Ok(PackedAtlas {
+            image,
+            rects: names.zip(rects).collect(),
+        })
+    }
+}
+
+impl Default for TextureAtlas {
+    fn default() -> Self {
+        TextureAtlas::new()
+    }
+}
+
+/// The result of packing a `TextureAtlas`: the image and where each image is in it.
+#[derive(Debug)]
+pub struct PackedAtlas {
+    image: Image,
+    rects: HashMap<String, IntRect>,
+}
+
+impl PackedAtlas {
+    /// Return the packed image.
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Return the packed image, consuming the atlas.
+    pub fn into_image(self) -> Image {
+        self.image
+    }
+
+    /// Create a texture from the packed image.
+    pub fn texture(&self) -> error::Result<Texture> {
+        Texture::from_image(&self.image)
+    }
+
+    /// Return the area of an image in the atlas, to use as a texture rect.
+    pub fn rect(&self, name: &str) -> Option<IntRect> {
+        self.rects.get(name).cloned()
+    }
+
+    /// Return the areas of all the images in the atlas, by name.
+    pub fn rects(&self) -> &HashMap<String, IntRect> {
+        &self.rects
+    }
+}
+
+/// An unsigned rectangle, for packing.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Area {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Area {
+    fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    fn contains(&self, other: &Area) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() &&
+        other.bottom() <= self.bottom()
+    }
+
+    fn intersects(&self, other: &Area) -> bool {
+        self.x < other.right() && other.x < self.right() && self.y < other.bottom() &&
+        other.y < self.bottom()
+    }
+}
+
+/// Find positions for rectangles of the given sizes, and the size of the atlas holding them.
+///
+/// Each rectangle is surrounded by `extrusion` pixels, and `padding` pixels separate them
+/// from each other and from the edges of the atlas. Returns `None` if the rectangles don't
+/// fit in `max_size` x `max_size` pixels.
+///
+/// The rectangles are placed with the MaxRects algorithm, largest first, in a bin whose
+/// power-of-two sides are doubled until everything fits, the last try using `max_size` for
+/// sides that would exceed it. The atlas is then trimmed to the used area. The returned rects
+/// are in the same order as `sizes` and don't include the extruded pixels.
+pub fn pack_rects(sizes: &[Vector2u],
+                  padding: u32,
+                  extrusion: u32,
+                  max_size: u32)
+                  -> Option<(Vector2u, Vec<IntRect>)> {
+    // The rectangles take their extruded pixels, and the padding at their right and bottom
+    let cells: Vec<(u32, u32)> = sizes.iter()
+        .map(|size| (size.x + 2 * extrusion + padding, size.y + 2 * extrusion + padding))
+        .collect();
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| {
+        let (width, height) = cells[i];
+        cmp::Reverse((cmp::max(width, height), width * height))
+    });
+
+    let area: u64 = cells.iter().map(|&(w, h)| u64::from(w) * u64::from(h)).sum();
+    let widest = cells.iter().map(|c| c.0).max().unwrap_or(0) + padding;
+    let highest = cells.iter().map(|c| c.1).max().unwrap_or(0) + padding;
+    let side = ((area as f64).sqrt() as u32).next_power_of_two();
+    let (mut width, mut height) = (cmp::min(cmp::max(side, widest.next_power_of_two()), max_size),
+                                   cmp::min(cmp::max(side, highest.next_power_of_two()), max_size));
+    loop {
+        if let Some(positions) = max_rects(&cells, &order, width, height, padding) {
+            let mut used = Vector2u::new(0, 0);
+            let rects = positions.iter()
+                .zip(sizes)
+                .map(|(&(x, y), size)| {
+                    used.x = cmp::max(used.x, x + size.x + 2 * extrusion + padding);
+                    used.y = cmp::max(used.y, y + size.y + 2 * extrusion + padding);
+                    IntRect::new((x + extrusion) as i32,
+                                 (y + extrusion) as i32,
+                                 size.x as i32,
+                                 size.y as i32)
+                })
+                .collect();
+            return Some((used, rects));
+        }
+        if width == max_size && height == max_size {
+            return None;
+        }
+        if height == max_size || (width <= height && width < max_size) {
+            width = cmp::min(width.saturating_mul(2), max_size);
+        } else {
+            height = cmp::min(height.saturating_mul(2), max_size);
+        }
+    }
+}
+
+/// Place the cells in `order` in a bin, returning the top-left corner of each cell.
+fn max_rects(cells: &[(u32, u32)],
+             order: &[usize],
+             width: u32,
+             height: u32,
+             padding: u32)
+             -> Option<Vec<(u32, u32)>> {
+    // The padding at the top and left edges, the cells bring it at the other edges
+    let mut free = vec![Area {
+                            x: padding,
+                            y: padding,
+                            width: width.saturating_sub(padding),
+                            height: height.saturating_sub(padding),
+                        }];
+    let mut positions = vec![(0, 0); cells.len()];
+    for &i in order {
+        let (cell_width, cell_height) = cells[i];
+        // Best short side fit, then best long side fit
+        let best = free.iter()
+            .filter(|f| f.width >= cell_width && f.height >= cell_height)
+            .min_by_key(|f| {
+                let (dx, dy) = (f.width - cell_width, f.height - cell_height);
+                (cmp::min(dx, dy), cmp::max(dx, dy))
+            })
+            .cloned()?;
+        let placed = Area {
+            x: best.x,
+            y: best.y,
+            width: cell_width,
+            height: cell_height,
+        };
+        positions[i] = (placed.x, placed.y);
+
+        // Split the free areas overlapping the cell into the parts around it
+        let mut split = Vec::with_capacity(free.len() + 4);
+        for f in free {
+            if !f.intersects(&placed) {
+                split.push(f);
+                continue;
+            }
+            if placed.x > f.x {
+                split.push(Area { width: placed.x - f.x, ..f });
+            }
+            if placed.right() < f.right() {
+                split.push(Area {
+                    x: placed.right(),
+                    width: f.right() - placed.right(),
+                    ..f
+                });
+            }
+            if placed.y > f.y {
+                split.push(Area { height: placed.y - f.y, ..f });
+            }
+            if placed.bottom() < f.bottom() {
+                split.push(Area {
+                    y: placed.bottom(),
+                    height: f.bottom() - placed.bottom(),
+                    ..f
+                });
+            }
+        }
+        // Drop the areas contained in others, keeping one of identical areas
+        free = split.iter()
+            .enumerate()
+            .filter(|&(j, a)| {
+                !split.iter()
+                    .enumerate()
+                    .any(|(k, b)| k != j && b.contains(a) && (a != b || k < j))
+            })
+            .map(|(_, &a)| a)
+            .collect();
+    }
+    Some(positions)
+}
+
+/// Repeat the edge pixels of `rect` around it, `extrusion` times.
+fn extrude(pixels: &mut [Color], width: u32, rect: &IntRect, extrusion: u32) {
+    if rect.width <= 0 || rect.height <= 0 || extrusion == 0 {
+        return;
+    }
+    let width = width as usize;
+    let (left, top) = (rect.left as usize, rect.top as usize);
+    let (right, bottom) = (left + rect.width as usize, top + rect.height as usize);
+    let extrusion = extrusion as usize;
+    for y in top..bottom {
+        let row = y * width;
+        for k in 1..=extrusion {
+            pixels[row + left - k] = pixels[row + left];
+            pixels[row + right - 1 + k] = pixels[row + right - 1];
+        }
+    }
+    let (start, end) = (left - extrusion, right + extrusion);
+    for k in 1..=extrusion {
+        for &(from, to) in &[(top, top - k), (bottom - 1, bottom - 1 + k)] {
+            let (from, to) = (from * width, to * width);
+            pixels.copy_within(from + start..from + end, to + start);
+        }
+    }
+}
+
+#[test]
+fn pack_and_extrude() {
+    let sizes = [Vector2u::new(30, 10),
+                 Vector2u::new(10, 30),
+                 Vector2u::new(16, 16),
+                 Vector2u::new(4, 4),
+                 Vector2u::new(30, 10)];
+    let (size, rects) = pack_rects(&sizes, 2, 1, 64).unwrap();
+    assert!(size.x <= 64 && size.y <= 64);
+    for (i, a) in rects.iter().enumerate() {
+        assert_eq!((a.width as u32, a.height as u32), (sizes[i].x, sizes[i].y));
+        // Padding and extruded pixels around each rect stay inside the atlas
+        assert!(a.left >= 3 && a.top >= 3);
+        assert!(a.right() + 3 <= size.x as i32 && a.bottom() + 3 <= size.y as i32);
+        // 1 extruded pixel on each side and 2 pixels of padding make 4 pixels between rects
+        for b in &rects[i + 1..] {
+            assert_eq!(a.inflate(2, 2).intersection(&b.inflate(2, 2)), None);
+        }
+    }
+    assert_eq!(pack_rects(&sizes, 2, 1, 32), None);
+    assert_eq!(pack_rects(&[], 2, 1, 32), Some((Vector2u::new(0, 0), vec![])));
+    // Too large for 2048 x 2048, the next try is clamped to the maximum size
+    let large = [Vector2u::new(1400, 1400), Vector2u::new(1400, 1400)];
+    assert_eq!(pack_rects(&large, 0, 0, 3000).map(|packed| packed.0),
+               Some(Vector2u::new(2800, 1400)));
+    assert_eq!(pack_rects(&large, 0, 0, 2048), None);
+
+    let (red, blue) = (Color::red(), Color::blue());
+    let t = Color::transparent();
+    let mut pixels = vec![t, t, t, t, t, red, blue, t, t, t, t, t, t, t, t, t];
+    extrude(&mut pixels, 4, &IntRect::new(1, 1, 2, 1), 1);
+    assert_eq!(pixels,
+               [red, red, blue, blue, red, red, blue, blue, red, red, blue, blue, t, t, t, t]);
+}
@@ -0,0 +1,431 @@
+use graphics::{Color, Drawable, FloatRect, PrimitiveType, RenderStates, RenderTarget, TextureRef,
+               Transform, Transformable, Vertex, VertexArray};
+use graphics::transformable::TransformableState;
+use std::ops::Range;
+use system::{Vector2f, Vector2u};
+
+/// The default width and height of a chunk, in tiles.
+const DEFAULT_CHUNK_SIZE: u32 = 32;
+
+/// A grid of tiles drawn from a tileset texture, for large 2D maps.
+///
+/// Tiles are identified by their index in the tileset, counted from left to right and top to
+/// bottom, and `None` is an empty tile. The map is split in square chunks of tiles, each
+/// stored in its own `VertexArray` of quads. When drawn, only the chunks intersecting the
+/// current `View` of the target are sent to the graphics card, which keeps the cost of drawing
+/// proportional to the visible area instead of the size of the map.
+///
+/// Changing a tile only rewrites its 4 vertices, so maps can be edited every frame.
+///
+/// Like `Sprite`, it is `Transformable`.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{RenderTarget, RenderWindow, Texture, TileMap};
+/// use sfml::window::style;
+///
+/// let mut window = RenderWindow::new((800, 600), "Tiles", style::CLOSE, &Default::default());
+/// // A tileset of 16x16 tiles
+/// let tileset = Texture::from_file("tileset.png").unwrap();
+/// let mut map = TileMap::new(&tileset, (16, 16), (1000, 1000));
+/// let grass = vec![Some(0); 1000 * 1000];
+/// map.set_tiles(&grass);
+/// // A well
+/// map.set_tile(12, 7, Some(5));
+/// window.draw(&map);
+/// window.display();
+/// ```
+#[derive(Debug)]
+pub struct TileMap<'s> {
+    texture: &'s TextureRef,
+    tile_size: Vector2u,
+    map_size: Vector2u,
+    chunk_size: u32,
+    tiles: Vec<Option<u32>>,
+    chunks: Vec<VertexArray>,
+    transformable: TransformableState,
+}
+
+impl<'s> TileMap<'s> {
+    /// Create an empty map of `map_size` tiles of `tile_size` pixels, using `texture` as
+    /// tileset.
+    pub fn new<T, M>(texture: &'s TextureRef, tile_size: T, map_size: M) -> TileMap<'s>
+        where T: Into<Vector2u>,
+              M: Into<Vector2u>
+    {
+        let map_size = map_size.into();
+        let mut map = TileMap {
+            texture,
+            tile_size: tile_size.into(),
+            map_size,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            tiles: vec![None; (map_size.x * map_size.y) as usize],
+            chunks: Vec::new(),
+            transformable: TransformableState::default(),
+        };
+        map.rebuild();
+        map
+    }
+
+    /// Change the tileset, keeping the tiles.
+    pub fn set_texture(&mut self, texture: &'s TextureRef) {
+        self.texture = texture;
+        self.rebuild();
+    }
+
+    /// Return the tileset.
+    pub fn texture(&self) -> &'s TextureRef {
+        self.texture
+    }
+
+    /// Return the size of a tile, in pixels.
+    pub fn tile_size(&self) -> Vector2u {
+        self.tile_size
+    }
+
+    /// Return the size of the map, in tiles.
+    pub fn map_size(&self) -> Vector2u {
+        self.map_size
+    }
+
+    /// Set the width and height of the chunks, in tiles, 32 by default.
+    ///
+    /// Smaller chunks cull more precisely but need more draw calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn set_chunk_size(&mut self, chunk_size: u32) {
+        assert!(chunk_size > 0, "The chunk size of a TileMap can't be 0");
+        self.chunk_size = chunk_size;
+        self.rebuild();
+    }
+
+    /// Return the width and height of the chunks, in tiles.
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    /// Return the number of chunks of the map.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Return the tile at `(x, y)`, or `None` if it is empty or out of the map.
+    pub fn tile(&self, x: u32, y: u32) -> Option<u32> {
+        if x < self.map_size.x && y < self.map_size.y {
+            self.tiles[(y * self.map_size.x + x) as usize]
+        } else {
+            None
+        }
+    }
+
+    /// Set the tile at `(x, y)`, `None` to empty it.
+    ///
+    /// Only the vertices of this tile are updated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `(x, y)` is out of the map.
+    pub fn set_tile(&mut self, x: u32, y: u32, tile: Option<u32>) {
+        assert!(x < self.map_size.x && y < self.map_size.y,
+                "Tile ({}, {}) is out of the {}x{} map",
+                x,
+                y,
+                self.map_size.x,
+                self.map_size.y);
+        self.tiles[(y * self.map_size.x + x) as usize] = tile;
+        self.layout().write_tile(&mut self.chunks, x, y, tile);
+    }
+
+    /// Set all the tiles, row by row, and rebuild the whole map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tiles` doesn't contain exactly one tile per cell of the map.
+    pub fn set_tiles(&mut self, tiles: &[Option<u32>]) {
+        assert_eq!(tiles.len(),
+                   self.tiles.len(),
+                   "Wrong number of tiles for a {}x{} map",
+                   self.map_size.x,
+                   self.map_size.y);
+        self.tiles.copy_from_slice(tiles);
+        self.rebuild();
+    }
+
+    /// Return the coordinates of the tile under `point`, given in the coordinates of the
+    /// parent of the map (after its transform), or `None` if the point is out of the map.
+    pub fn tile_coords<P: Into<Vector2f>>(&self, point: P) -> Option<Vector2u> {
+        let local = self.inverse_transform().transform_point(&point.into());
+        let x = (local.x / self.tile_size.x as f32).floor();
+        let y = (local.y / self.tile_size.y as f32).floor();
+        if x >= 0. && y >= 0. && x < self.map_size.x as f32 && y < self.map_size.y as f32 {
+            Some(Vector2u::new(x as u32, y as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Return the bounds of the map, before its transform.
+    pub fn local_bounds(&self) -> FloatRect {
+        FloatRect::new(0.,
+                       0.,
+                       (self.map_size.x * self.tile_size.x) as f32,
+                       (self.map_size.y * self.tile_size.y) as f32)
+    }
+
+    /// Return the bounds of the map in the coordinates of its parent, after its transform.
+    pub fn global_bounds(&self) -> FloatRect {
+        self.transform().transform_rect(&self.local_bounds())
+    }
+
+    fn layout(&self) -> Layout {
+        Layout {
+            map_size: self.map_size,
+            tile_size: self.tile_size,
+            chunk_size: self.chunk_size,
+            // Number of tiles per row of the tileset
+            columns: (self.texture.size().x / self.tile_size.x.max(1)).max(1),
+        }
+    }
+
+    /// Rebuild the vertices of all the chunks.
+    fn rebuild(&mut self) {
+        self.chunks = self.layout().build_chunks(&self.tiles);
+    }
+}
+
+/// How the tiles of a map are laid out in chunks of vertices.
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    map_size: Vector2u,
+    tile_size: Vector2u,
+    chunk_size: u32,
+    columns: u32,
+}
+
+impl Layout {
+    /// Return the number of chunks along each axis.
+    fn chunk_grid(&self) -> Vector2u {
+        Vector2u::new(self.map_size.x.div_ceil(self.chunk_size),
+                      self.map_size.y.div_ceil(self.chunk_size))
+    }
+
+    /// Return the chunk of the tile at `(x, y)`, and the index of its first vertex in it.
+    ///
+    /// The tiles of a chunk are stored row by row, chunks on the right and bottom edges
+    /// of the map are narrower or shorter than the others.
+    fn vertex_index(&self, x: u32, y: u32) -> (usize, usize) {
+        let (chunk_x, chunk_y) = (x / self.chunk_size, y / self.chunk_size);
+        let chunk = chunk_y * self.chunk_grid().x + chunk_x;
+        let chunk_width = self.chunk_size.min(self.map_size.x - chunk_x * self.chunk_size);
+        let (x, y) = (x % self.chunk_size, y % self.chunk_size);
+        (chunk as usize, (y * chunk_width + x) as usize * 4)
+    }
+
+    /// Build the vertices of all the chunks, `tiles` being the tiles of the map row by row.
+    fn build_chunks(&self, tiles: &[Option<u32>]) -> Vec<VertexArray> {
+        let grid = self.chunk_grid();
+        let mut chunks = Vec::with_capacity((grid.x * grid.y) as usize);
+        for chunk_y in 0..grid.y {
+            for chunk_x in 0..grid.x {
+                let xs = chunk_x * self.chunk_size..
+                         self.map_size.x.min((chunk_x + 1) * self.chunk_size);
+                let ys = chunk_y * self.chunk_size..
+                         self.map_size.y.min((chunk_y + 1) * self.chunk_size);
+                let mut vertices = VertexArray::new(PrimitiveType::Quads, 0);
+                for y in ys {
+                    for x in xs.clone() {
+                        let tile = tiles[(y * self.map_size.x + x) as usize];
+                        for vertex in &tile_quad(x, y, self.tile_size, tile, self.columns) {
+                            vertices.append(vertex);
+                        }
+                    }
+                }
+                chunks.push(vertices);
+            }
+        }
+        chunks
+    }
+
+    /// Rewrite the 4 vertices of the tile at `(x, y)` in `chunks`.
+    fn write_tile(&self, chunks: &mut [VertexArray], x: u32, y: u32, tile: Option<u32>) {
+        let (chunk, index) = self.vertex_index(x, y);
+        let quad = tile_quad(x, y, self.tile_size, tile, self.columns);
+        for (i, vertex) in quad.iter().enumerate() {
+            chunks[chunk][index + i] = *vertex;
+        }
+    }
+}
+
+/// Return the quad of the tile at `(x, y)`.
+///
+/// Empty tiles get a transparent quad of zero area, which draws nothing but keeps the
+/// vertices of every tile at a fixed place in its chunk.
+fn tile_quad(x: u32,
+             y: u32,
+             tile_size: Vector2u,
+             tile: Option<u32>,
+             columns: u32)
+             -> [Vertex; 4] {
+    let (width, height) = (tile_size.x as f32, tile_size.y as f32);
+    let (left, top) = (x as f32 * width, y as f32 * height);
+    let tile = match tile {
+        Some(tile) => tile,
+        None => {
+            let vertex = Vertex::new((left, top), Color::transparent(), Vector2f::new(0., 0.));
+            return [vertex; 4];
+        }
+    };
+    let u = (tile % columns) as f32 * width;
+    let v = (tile / columns) as f32 * height;
+    let vertex = |dx: f32, dy: f32| {
+        Vertex::new((left + dx, top + dy), Color::white(), Vector2f::new(u + dx, v + dy))
+    };
+    [vertex(0., 0.), vertex(width, 0.), vertex(width, height), vertex(0., height)]
+}
+
+/// Return the ranges of chunks intersecting `area`, in the local coordinates of the map.
+fn visible_chunks(area: &FloatRect,
+                  chunk_extent: Vector2f,
+                  grid: Vector2u)
+                  -> (Range<u32>, Range<u32>) {
+    let range = |start: f32, length: f32, extent: f32, count: u32| {
+        let first = (start / extent).floor().max(0.);
+        let last = ((start + length) / extent).ceil().min(count as f32);
+        if first < last {
+            first as u32..last as u32
+        } else {
+            0..0
+        }
+    };
+    (range(area.left, area.width, chunk_extent.x, grid.x),
+     range(area.top, area.height, chunk_extent.y, grid.y))
+}
+
+impl<'s> Drawable for TileMap<'s> {
+    fn draw<'se, 'tex, 'sh, 'shte>(&'se self,
+                                   target: &mut dyn RenderTarget,
+                                   states: RenderStates<'tex, 'sh, 'shte>)
+        where 'se: 'sh
+    {
+        let transform = states.transform * self.transform();
+        // The area seen by the view, brought back to the coordinates of the map
+        let view = target.view();
+        let (center, size) = (view.center(), view.size());
+        let seen = FloatRect::new(center.x - size.x / 2., center.y - size.y / 2., size.x, size.y);
+        let seen = Transform::identity()
+            .rotated_with_center(view.rotation(), center.x, center.y)
+            .transform_rect(&seen);
+        let area = transform.inverse().transform_rect(&seen);
+
+        let chunk_extent = Vector2f::new((self.chunk_size * self.tile_size.x) as f32,
+                                         (self.chunk_size * self.tile_size.y) as f32);
+        let grid = self.layout().chunk_grid();
+        let (xs, ys) = visible_chunks(&area, chunk_extent, grid);
+        for chunk_y in ys {
+            for chunk_x in xs.clone() {
+                let states = RenderStates::new(states.blend_mode,
+                                               transform,
+                                               Some(self.texture),
+                                               states.shader);
+                let chunk = &self.chunks[(chunk_y * grid.x + chunk_x) as usize];
+                target.draw_vertex_array(chunk, states);
+            }
+        }
+    }
+}
+
+impl<'s> Transformable for TileMap<'s> {
+    fn set_position<P: Into<Vector2f>>(&mut self, position: P) {
+        self.transformable.set_position(position)
+    }
+    fn set_rotation(&mut self, angle: f32) {
+        self.transformable.set_rotation(angle)
+    }
+    fn set_scale<S: Into<Vector2f>>(&mut self, scale: S) {
+        self.transformable.set_scale(scale)
+    }
+    fn set_origin<O: Into<Vector2f>>(&mut self, origin: O) {
+        self.transformable.set_origin(origin)
+    }
+    fn position(&self) -> Vector2f {
+        self.transformable.position()
+    }
+    fn rotation(&self) -> f32 {
+        self.transformable.rotation()
+    }
+    fn get_scale(&self) -> Vector2f {
+        self.transformable.get_scale()
+    }
+    fn origin(&self) -> Vector2f {
+        self.transformable.origin()
+    }
+    fn move_<O: Into<Vector2f>>(&mut self, offset: O) {
+        self.transformable.move_(offset)
+    }
+    fn rotate(&mut self, angle: f32) {
+        self.transformable.rotate(angle)
+    }
+    fn scale<F: Into<Vector2f>>(&mut self, factors: F) {
+        self.transformable.scale(factors)
+    }
+    fn transform(&self) -> Transform {
+        self.transformable.transform()
+    }
+    fn inverse_transform(&self) -> Transform {
+        self.transformable.inverse_transform()
+    }
+}
+
+#[test]
+fn tiles_and_culling() {
+    let tile_size = Vector2u::new(16, 8);
+    // Tile 5 of a tileset of 4 columns is on the second row
+    let quad = tile_quad(2, 3, tile_size, Some(5), 4);
+    assert_eq!(quad[0].position, Vector2f::new(32., 24.));
+    assert_eq!(quad[2].position, Vector2f::new(48., 32.));
+    assert_eq!(quad[0].tex_coords, Vector2f::new(16., 8.));
+    assert_eq!(quad[2].tex_coords, Vector2f::new(32., 16.));
+    let empty = tile_quad(2, 3, tile_size, None, 4);
+    assert!(empty.iter().all(|v| v.position == Vector2f::new(32., 24.)));
+    assert!(empty.iter().all(|v| v.color == Color::transparent()));
+
+    let extent = Vector2f::new(100., 50.);
+    let grid = Vector2u::new(10, 10);
+    let area = FloatRect::new(150., -20., 200., 90.);
+    assert_eq!(visible_chunks(&area, extent, grid), (1..4, 0..2));
+    // Areas reaching past the map are clamped, areas out of it see nothing
+    let area = FloatRect::new(850., 480., 500., 500.);
+    assert_eq!(visible_chunks(&area, extent, grid), (8..10, 9..10));
+    let area = FloatRect::new(-300., 0., 200., 100.);
+    assert_eq!(visible_chunks(&area, extent, grid), (0..0, 0..2));
+
+    // Updating single tiles gives the same vertices as rebuilding, edge chunks included
+    let layout = Layout {
+        map_size: Vector2u::new(5, 3),
+        tile_size,
+        chunk_size: 2,
+        columns: 4,
+    };
+    let mut tiles: Vec<Option<u32>> =
+        (0..15).map(|i| if i % 4 == 0 { None } else { Some(i) }).collect();
+    let mut chunks = layout.build_chunks(&tiles);
+    let counts: Vec<usize> = chunks.iter().map(|c| c.vertex_count()).collect();
+    assert_eq!(counts, vec![16, 16, 8, 8, 8, 4]);
+    for y in 0..3 {
+        for x in 0..5 {
+            let tile = if (x + y) % 3 == 0 { None } else { Some(20 + y * 5 + x) };
+            tiles[(y * 5 + x) as usize] = tile;
+            layout.write_tile(&mut chunks, x, y, tile);
+        }
+    }
+    let rebuilt = layout.build_chunks(&tiles);
+    for (chunk, expected) in chunks.iter().zip(&rebuilt) {
+        for i in 0..expected.vertex_count() {
+            assert_eq!((chunk[i].position, chunk[i].color, chunk[i].tex_coords),
+                       (expected[i].position, expected[i].color, expected[i].tex_coords));
+        }
+    }
+}
@@ -59,3 +59,82 @@ pub trait Transformable {
     /// Gets the inverse combined transform of the object.
     fn inverse_transform(&self) -> Transform;
 }
+
+/// The position, rotation, scale and origin of a `Transformable` drawable implemented in Rust.
+///
+/// Drawables keep one of these and delegate their `Transformable` implementation to it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TransformableState {
+    position: Vector2f,
+    rotation: f32,
+    scale: Vector2f,
+    origin: Vector2f,
+}
+
+impl Default for TransformableState {
+    fn default() -> Self {
+        TransformableState {
+            position: Vector2f::new(0., 0.),
+            rotation: 0.,
+            scale: Vector2f::new(1., 1.),
+            origin: Vector2f::new(0., 0.),
+        }
+    }
+}
+
+impl Transformable for TransformableState {
+    fn set_position<P: Into<Vector2f>>(&mut self, position: P) {
+        self.position = position.into();
+    }
+    fn set_rotation(&mut self, angle: f32) {
+        self.rotation = angle % 360.;
+        if self.rotation < 0. {
+            self.rotation += 360.;
+        }
+    }
+    fn set_scale<S: Into<Vector2f>>(&mut self, scale: S) {
+        self.scale = scale.into();
+    }
+    fn set_origin<O: Into<Vector2f>>(&mut self, origin: O) {
+        self.origin = origin.into();
+    }
+    fn position(&self) -> Vector2f {
+        self.position
+    }
+    fn rotation(&self) -> f32 {
+        self.rotation
+    }
+    fn get_scale(&self) -> Vector2f {
+        self.scale
+    }
+    fn origin(&self) -> Vector2f {
+        self.origin
+    }
+    fn move_<O: Into<Vector2f>>(&mut self, offset: O) {
+        self.position += offset.into();
+    }
+    fn rotate(&mut self, angle: f32) {
+        let rotation = self.rotation + angle;
+        self.set_rotation(rotation);
+    }
+    fn scale<F: Into<Vector2f>>(&mut self, factors: F) {
+        let factors = factors.into();
+        self.scale.x *= factors.x;
+        self.scale.y *= factors.y;
+    }
+    /// The same combination as `sf::Transformable`: scale and rotate around the origin,
+    /// then translate to the position.
+    fn transform(&self) -> Transform {
+        let (sin, cos) = (-self.rotation).to_radians().sin_cos();
+        let sxc = self.scale.x * cos;
+        let syc = self.scale.y * cos;
+        let sxs = self.scale.x * sin;
+        let sys = self.scale.y * sin;
+        let tx = -self.origin.x * sxc - self.origin.y * sys + self.position.x;
+        let ty = self.origin.x * sxs - self.origin.y * syc + self.position.y;
+        Transform::new([sxc, sys, tx, -sxs, syc, ty, 0., 0., 1.])
+    }
+    fn inverse_transform(&self) -> Transform {
+        self.transform().inverse()
+    }
+}
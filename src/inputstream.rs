@@ -1,62 +1,201 @@
 use csfml_system_sys::sfInputStream;
-use std::io::{Read, Seek, SeekFrom};
+use error::{Error, ErrorKind};
+use std::any::Any;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
 use std::os::raw::{c_longlong, c_void};
-use std::ptr;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
 
-unsafe extern "C" fn read<T: Read + Seek>(data: *mut c_void,
-                                          size: c_longlong,
-                                          user_data: *mut c_void)
-                                          -> c_longlong {
-    let stream: &mut T = &mut *(user_data as *mut T);
-    if size == 0 {
-        return 0;
-    } else if size > 0 {
-        let mut chunk = stream.take(size as u64);
-        let mut buf = vec![];
-        let status = chunk.read_to_end(&mut buf);
-        if status.is_ok() {
-            ptr::copy_nonoverlapping(buf.as_ptr(), data as *mut u8, size as usize);
-            return status.unwrap() as i64;
+/// Object-safe combination of `Read` and `Seek`.
+trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+/// The state shared with SFML through `sfInputStream::userData`.
+struct Source<'a> {
+    stream: Box<dyn ReadSeek + 'a>,
+    /// The first I/O error reported by the stream.
+    error: Option<io::Error>,
+    /// The payload of a panic raised by the stream, resumed once SFML returns control.
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<'a> Source<'a> {
+    /// Runs an operation on the stream, turning errors and panics into `-1`.
+    fn call<F>(&mut self, f: F) -> c_longlong
+        where F: FnOnce(&mut dyn ReadSeek) -> io::Result<u64>
+    {
+        // A stream that panicked is left in an unknown state, don't touch it again.
+        if self.panic.is_some() {
+            return -1;
+        }
+        let stream = &mut *self.stream;
+        match panic::catch_unwind(AssertUnwindSafe(|| f(stream))) {
+            Ok(Ok(value)) => value as c_longlong,
+            Ok(Err(e)) => {
+                if self.error.is_none() {
+                    self.error = Some(e);
+                }
+                -1
+            }
+            Err(payload) => {
+                self.panic = Some(payload);
+                -1
+            }
         }
     }
-    -1
 }
 
-unsafe extern "C" fn get_size<T: Read + Seek>(user_data: *mut c_void) -> c_longlong {
-    let stream: &mut T = &mut *(user_data as *mut T);
-    let pos = stream.seek(SeekFrom::Current(0)).unwrap();
-    let size = stream.seek(SeekFrom::End(0)).unwrap();
-    let _ = stream.seek(SeekFrom::Start(pos));
-    size as i64
+unsafe fn source<'a>(user_data: *mut c_void) -> &'a mut Source<'a> {
+    &mut *(user_data as *mut Source)
 }
 
-unsafe extern "C" fn tell<T: Read + Seek>(user_data: *mut c_void) -> c_longlong {
-    let stream: &mut T = &mut *(user_data as *mut T);
-    stream.seek(SeekFrom::Current(0)).unwrap() as i64
+unsafe extern "C" fn read(data: *mut c_void,
+                          size: c_longlong,
+                          user_data: *mut c_void)
+                          -> c_longlong {
+    if size <= 0 {
+        return if size == 0 { 0 } else { -1 };
+    }
+    let buf = slice::from_raw_parts_mut(data as *mut u8, size as usize);
+    source(user_data).call(|stream| {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match stream.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(filled as u64)
+    })
 }
 
-unsafe extern "C" fn seek<T: Read + Seek>(position: c_longlong,
-                                          user_data: *mut c_void)
-                                          -> c_longlong {
-    let stream: &mut T = &mut *(user_data as *mut T);
-    match stream.seek(SeekFrom::Start(position as u64)) {
-        Ok(n) => n as i64,
-        Err(_) => -1,
+unsafe extern "C" fn get_size(user_data: *mut c_void) -> c_longlong {
+    source(user_data).call(|stream| {
+        let pos = stream.stream_position()?;
+        let size = stream.seek(SeekFrom::End(0))?;
+        let _ = stream.seek(SeekFrom::Start(pos))?;
+        Ok(size)
+    })
+}
+
+unsafe extern "C" fn tell(user_data: *mut c_void) -> c_longlong {
+    source(user_data).call(|stream| stream.stream_position())
+}
+
+unsafe extern "C" fn seek(position: c_longlong, user_data: *mut c_void) -> c_longlong {
+    if position < 0 {
+        return -1;
     }
+    source(user_data).call(|stream| stream.seek(SeekFrom::Start(position as u64)))
+}
+
+/// Bridge between a Rust `Read + Seek` stream and SFML's `sfInputStream`.
+///
+/// I/O errors and panics raised by the stream never cross the FFI boundary: they are
+/// recorded, reported to SFML as a failed operation, and surfaced by `finish` once SFML
+/// returns control.
+pub struct InputStream<'a> {
+    raw: sfInputStream,
+    source: Box<Source<'a>>,
 }
 
-#[repr(C)]
-pub struct InputStream(pub sfInputStream);
+impl<'a> fmt::Debug for InputStream<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InputStream").finish()
+    }
+}
 
-impl InputStream {
-    pub fn new<T: Read + Seek>(stream: &mut T) -> Self {
-        let user_data: *const T = stream;
-        InputStream(sfInputStream {
-                        userData: user_data as *mut c_void,
-                        read: Some(read::<T>),
-                        seek: Some(seek::<T>),
-                        tell: Some(tell::<T>),
-                        getSize: Some(get_size::<T>),
-                    })
+impl<'a> InputStream<'a> {
+    pub fn new<T: Read + Seek + 'a>(stream: T) -> Self {
+        let mut source = Box::new(Source {
+                                      stream: Box::new(stream),
+                                      error: None,
+                                      panic: None,
+                                  });
+        let user_data: *mut Source = &mut *source;
+        InputStream {
+            raw: sfInputStream {
+                userData: user_data as *mut c_void,
+                read: Some(read),
+                seek: Some(seek),
+                tell: Some(tell),
+                getSize: Some(get_size),
+            },
+            source,
+        }
+    }
+
+    /// The pointer to pass to SFML. It stays valid as long as `self` is alive.
+    pub fn raw_mut(&mut self) -> *mut sfInputStream {
+        &mut self.raw
+    }
+
+    /// Resumes the panic raised by the stream, if any, and takes the recorded I/O error.
+    pub fn finish(&mut self) -> Option<io::Error> {
+        if let Some(payload) = self.source.panic.take() {
+            panic::resume_unwind(payload);
+        }
+        self.source.error.take()
+    }
+
+    /// Build the error for a resource that SFML failed to load from this stream.
+    pub fn load_failure(&mut self, message: String) -> Error {
+        match self.finish() {
+            Some(e) => Error::from(e),
+            None => Error::new(ErrorKind::DecodeFailed),
+        }
+        .with_message(message)
+    }
+}
+
+#[test]
+fn reads_fill_destination() {
+    use std::io::Cursor;
+
+    let mut stream = InputStream::new(Cursor::new(b"hello world".to_vec()));
+    let user_data = stream.raw.userData;
+    let mut buf = [0u8; 5];
+    unsafe {
+        assert_eq!(get_size(user_data), 11);
+        assert_eq!(seek(6, user_data), 6);
+        assert_eq!(read(buf.as_mut_ptr() as *mut c_void, 5, user_data), 5);
+        assert_eq!(tell(user_data), 11);
+        assert_eq!(read(buf.as_mut_ptr() as *mut c_void, 5, user_data), 0);
+    }
+    assert_eq!(&buf, b"world");
+    assert!(stream.finish().is_none());
+}
+
+#[test]
+fn errors_and_panics_are_recorded() {
+    struct Broken;
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+    }
+    impl Seek for Broken {
+        fn seek(&mut self, _: SeekFrom) -> io::Result<u64> {
+            panic!("seek panicked")
+        }
+    }
+
+    let mut stream = InputStream::new(Broken);
+    let user_data = stream.raw.userData;
+    let mut buf = [0u8; 4];
+    unsafe {
+        assert_eq!(read(buf.as_mut_ptr() as *mut c_void, 4, user_data), -1);
+    }
+    assert_eq!(stream.finish().unwrap().to_string(), "disk on fire");
+    unsafe {
+        assert_eq!(tell(user_data), -1);
+        // The stream isn't touched again once it panicked.
+        assert_eq!(read(buf.as_mut_ptr() as *mut c_void, 4, user_data), -1);
     }
+    let resumed = panic::catch_unwind(AssertUnwindSafe(|| stream.finish()));
+    assert!(resumed.is_err());
 }
@@ -30,6 +30,9 @@ extern crate bitflags;
 extern crate csfml_system_sys;
 #[cfg(feature="window")]
 extern crate csfml_window_sys;
+#[cfg(feature="log")]
+#[macro_use]
+extern crate log;
 
 #[cfg(any(feature="graphics", feature="audio"))]
 mod inputstream;
@@ -37,6 +40,12 @@ mod sf_bool_ext;
 #[cfg(feature="window")]
 mod unicode_conv;
 
+pub mod error;
+pub use error::{Error, ErrorKind};
+
+pub mod assets;
+pub mod resource_cache;
+
 pub mod system;
 #[cfg(feature="window")]
 pub mod window;
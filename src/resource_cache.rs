@@ -0,0 +1,254 @@
+//! Sharing loaded resources between the objects that use them.
+//!
+//! Drawables and sounds borrow the resources they use (`Sprite<'s>` borrows a `Texture`,
+//! `Text<'s>` a `Font`, `Sound<'s>` a `SoundBuffer`), so the resources have to be stored
+//! somewhere that outlives them. A `ResourceCache` loads each resource once per key and
+//! hands out `Rc` handles to it: the resource stays alive as long as a handle does, even
+//! after it was evicted from the cache.
+//!
+//! # Usage example
+//!
+//! ```no_run
+//! use sfml::graphics::{Sprite, Texture};
+//! use sfml::resource_cache::ResourceCache;
+//!
+//! let mut textures = ResourceCache::new();
+//! let texture = textures.get_or_load("player.png", |path| Texture::from_file(path)).unwrap();
+//! // Loaded once, the second call returns the same texture
+//! let same = textures.get_or_load("player.png", |path| Texture::from_file(path)).unwrap();
+//!
+//! let mut sprite = Sprite::new();
+//! sprite.set_texture(&texture, true);
+//! println!("{} textures, {} bytes", textures.len(), textures.memory_usage());
+//! ```
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// Resources that can report how much memory they use.
+///
+/// The reported size is an estimate of the pixel or sample data held by the resource,
+/// in bytes, whether it lives in RAM or in video memory. Fonts report the size of their
+/// source file, and shaders, whose compiled programs are owned by the driver, report 0.
+pub trait MemoryUsage {
+    /// Return the approximate number of bytes used by the resource.
+    fn memory_usage(&self) -> usize;
+}
+
+#[cfg(feature="graphics")]
+mod graphics_usage {
+    use super::MemoryUsage;
+    use graphics::{Font, Image, RenderTexture, RenderTarget, Shader, Texture};
+
+    impl MemoryUsage for Font {
+        fn memory_usage(&self) -> usize {
+            self.source_size()
+        }
+    }
+
+    impl MemoryUsage for Image {
+        fn memory_usage(&self) -> usize {
+            let size = self.size();
+            size.x as usize * size.y as usize * 4
+        }
+    }
+
+    impl MemoryUsage for Texture {
+        fn memory_usage(&self) -> usize {
+            let size = self.size();
+            size.x as usize * size.y as usize * 4
+        }
+    }
+
+    impl MemoryUsage for RenderTexture {
+        fn memory_usage(&self) -> usize {
+            let size = self.size();
+            size.x as usize * size.y as usize * 4
+        }
+    }
+
+    impl<'te> MemoryUsage for Shader<'te> {
+        fn memory_usage(&self) -> usize {
+            0
+        }
+    }
+}
+
+#[cfg(feature="audio")]
+mod audio_usage {
+    use super::MemoryUsage;
+    use audio::SoundBuffer;
+    use std::mem;
+
+    impl MemoryUsage for SoundBuffer {
+        fn memory_usage(&self) -> usize {
+            self.sample_count() as usize * mem::size_of::<i16>()
+        }
+    }
+}
+
+/// A collection of resources of type `T`, loaded once and shared by key.
+///
+/// See the module documentation for an example.
+pub struct ResourceCache<K, T> {
+    resources: HashMap<K, Rc<T>>,
+}
+
+impl<K: Eq + Hash, T> ResourceCache<K, T> {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        ResourceCache { resources: HashMap::new() }
+    }
+
+    /// Return the resource stored for `key`, loading it with `load` if it isn't cached yet.
+    ///
+    /// Failures are not cached: the next call for the same key tries to load it again.
+    pub fn get_or_load<F, E>(&mut self, key: K, load: F) -> Result<Rc<T>, E>
+        where F: FnOnce(&K) -> Result<T, E>
+    {
+        match self.resources.entry(key) {
+            Entry::Occupied(entry) => Ok(entry.get().clone()),
+            Entry::Vacant(entry) => {
+                let resource = Rc::new(load(entry.key())?);
+                Ok(entry.insert(resource).clone())
+            }
+        }
+    }
+
+    /// Return the resource stored for `key`, if any.
+    pub fn get<Q>(&self, key: &Q) -> Option<Rc<T>>
+        where K: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.resources.get(key).cloned()
+    }
+
+    /// Store `resource` for `key`, replacing the previous one.
+    ///
+    /// Handles to the replaced resource stay valid.
+    pub fn insert(&mut self, key: K, resource: T) -> Rc<T> {
+        let resource = Rc::new(resource);
+        let _ = self.resources.insert(key, resource.clone());
+        resource
+    }
+
+    /// Tell whether a resource is stored for `key`.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+        where K: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.resources.contains_key(key)
+    }
+
+    /// Remove the resource stored for `key` from the cache.
+    ///
+    /// The resource itself is freed once the last handle to it is dropped.
+    pub fn evict<Q>(&mut self, key: &Q) -> Option<Rc<T>>
+        where K: Borrow<Q>,
+              Q: Eq + Hash + ?Sized
+    {
+        self.resources.remove(key)
+    }
+
+    /// Remove every resource that isn't used outside of the cache.
+    ///
+    /// Return the number of evicted resources
+    pub fn evict_unused(&mut self) -> usize {
+        let before = self.resources.len();
+        self.resources.retain(|_, resource| Rc::strong_count(resource) > 1);
+        before - self.resources.len()
+    }
+
+    /// Remove every resource from the cache.
+    pub fn clear(&mut self) {
+        self.resources.clear()
+    }
+
+    /// Return the number of cached resources.
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Tell whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// Return an iterator over the keys of the cached resources, in arbitrary order.
+    pub fn keys<'a>(&'a self) -> Keys<'a, K, T> {
+        Keys(self.resources.keys())
+    }
+}
+
+impl<K: Eq + Hash, T: MemoryUsage> ResourceCache<K, T> {
+    /// Return the approximate number of bytes used by the cached resources.
+    pub fn memory_usage(&self) -> usize {
+        self.resources.values().map(|r| r.memory_usage()).sum()
+    }
+}
+
+impl<K: Eq + Hash, T> Default for ResourceCache<K, T> {
+    fn default() -> Self {
+        ResourceCache::new()
+    }
+}
+
+impl<K: fmt::Debug, T> fmt::Debug for ResourceCache<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.resources.keys()).finish()
+    }
+}
+
+/// Iterator over the keys of a `ResourceCache`, see `ResourceCache::keys`.
+#[derive(Debug)]
+pub struct Keys<'a, K: 'a, T: 'a>(::std::collections::hash_map::Keys<'a, K, Rc<T>>);
+
+impl<'a, K, T> Iterator for Keys<'a, K, T> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[test]
+fn load_share_and_evict() {
+    struct Blob(usize);
+    impl MemoryUsage for Blob {
+        fn memory_usage(&self) -> usize {
+            self.0
+        }
+    }
+
+    let mut cache = ResourceCache::new();
+    let mut loads = 0;
+    let a = cache
+        .get_or_load("a", |_| -> Result<Blob, ()> {
+            loads += 1;
+            Ok(Blob(10))
+        })
+        .unwrap();
+    let again = cache.get_or_load("a", |_| Err(())).unwrap();
+    assert!(Rc::ptr_eq(&a, &again));
+    assert_eq!(loads, 1);
+    assert!(cache.get_or_load("missing", |_| Err("nope")).is_err());
+    assert!(!cache.contains("missing"));
+
+    let _ = cache.insert("b", Blob(5));
+    assert_eq!(cache.memory_usage(), 15);
+    assert_eq!(cache.evict_unused(), 1);
+    assert!(cache.contains("a") && !cache.contains("b"));
+
+    drop(again);
+    let evicted = cache.evict("a").unwrap();
+    assert!(Rc::ptr_eq(&a, &evicted));
+    assert!(cache.is_empty());
+}
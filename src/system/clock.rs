@@ -1,4 +1,6 @@
 use csfml_system_sys as ffi;
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
 use system::Time;
 use system::raw_conv::FromRaw;
 
@@ -45,6 +47,16 @@ impl Clock {
     pub fn restart(&mut self) -> Time {
         unsafe { Time::from_raw(ffi::sfClock_restart(self.0)) }
     }
+
+    /// Returns the `Instant` at which the clock was last (re)started.
+    ///
+    /// This relates the clock to other `std::time` based timers. The result is
+    /// approximate: it is derived from the elapsed time, not stored by the clock.
+    pub fn start_instant(&self) -> Instant {
+        let now = Instant::now();
+        let elapsed = Duration::try_from(self.elapsed_time()).unwrap_or_default();
+        now.checked_sub(elapsed).unwrap_or(now)
+    }
 }
 
 impl Clone for Clock {
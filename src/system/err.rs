@@ -0,0 +1,203 @@
+#[cfg(any(feature="graphics", feature="audio"))]
+use std::cell::Cell;
+use std::sync::{Arc, Mutex};
+
+/// A diagnostic message that SFML wrote to its error stream.
+///
+/// SFML reports most failures (images that can't be decoded, shaders that don't compile,
+/// missing audio devices..) by printing a message to `sf::err()`, which is the standard
+/// error output by default. While a handler is installed with `set_error_handler`, the
+/// loading functions of this crate intercept that output and hand it to the handler, together
+/// with the name of the function that triggered it.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorMessage<'a> {
+    origin: &'static str,
+    text: &'a str,
+}
+
+impl<'a> ErrorMessage<'a> {
+    /// The function that was running when SFML emitted the message, e.g. `"Shader::from_memory"`.
+    pub fn origin(&self) -> &'static str {
+        self.origin
+    }
+
+    /// The text written by SFML, including trailing newlines.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+}
+
+type Handler = Arc<dyn Fn(&ErrorMessage) + Send + Sync>;
+
+static HANDLER: Mutex<Option<Handler>> = Mutex::new(None);
+
+/// Installs a handler that receives every message SFML writes to its error stream
+/// while a loading function of this crate is running.
+///
+/// Installing a handler opts in to intercepting the messages. Without one, nothing is
+/// redirected and SFML writes its messages to the standard error output, as it does by
+/// default. While a handler is installed, the text of a failed load is additionally
+/// available through `Error::message`.
+///
+/// Messages are only intercepted on Unix-like systems, by the functions loading resources
+/// from files, memory or streams. On other systems the handler is stored but never called,
+/// and SFML keeps writing to the standard error output.
+///
+/// CSFML gives no access to `sf::err()`, so capturing works by redirecting the standard error
+/// file descriptor, which the whole process shares, while a loading function runs. As long
+/// as a handler is installed:
+///
+/// * loads are serialized: a texture, font, shader or sound loading on one thread waits for
+///   the loads running on the other threads;
+/// * anything other threads write to stderr during a load (panics, `eprintln!`, logging)
+///   doesn't reach the standard error output, it is passed to the handler as if SFML had
+///   written it.
+///
+/// Messages SFML prints outside of the loading functions (e.g. while drawing) are not
+/// intercepted.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::Shader;
+/// use sfml::system;
+///
+/// system::set_error_handler(|msg| println!("[{}] {}", msg.origin(), msg.text().trim()));
+/// let shader = Shader::from_memory(None, None, Some("not glsl"));
+/// ```
+pub fn set_error_handler<F>(handler: F)
+    where F: Fn(&ErrorMessage) + Send + Sync + 'static
+{
+    *HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(handler));
+}
+
+/// Removes the handler installed with `set_error_handler`.
+///
+/// SFML's messages are no longer intercepted and go to the standard error output again.
+pub fn reset_error_handler() {
+    *HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// An error handler that forwards SFML's messages to the `log` crate.
+///
+/// Messages are logged at the `warn` level, with the `sfml` target.
+///
+/// ```no_run
+/// sfml::system::set_error_handler(sfml::system::log_handler);
+/// ```
+#[cfg(feature="log")]
+pub fn log_handler(message: &ErrorMessage) {
+    warn!(target: "sfml", "{}: {}", message.origin(), message.text().trim_end());
+}
+
+#[cfg(any(feature="graphics", feature="audio"))]
+thread_local!(static CAPTURING: Cell<bool> = const { Cell::new(false) });
+
+/// Runs `f`, capturing everything SFML writes to its error stream in the meantime
+/// if an error handler is installed.
+///
+/// The captured text is passed to the error handler and returned along with the
+/// result of `f`. Nested captures are folded into the outermost one.
+#[cfg(any(feature="graphics", feature="audio"))]
+pub(crate) fn capture<R, F: FnOnce() -> R>(origin: &'static str, f: F) -> (R, String) {
+    // The handler is cloned out of the lock, so that it can install another handler
+    let handler = HANDLER.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let handler = match handler {
+        Some(handler) => handler,
+        None => return (f(), String::new()),
+    };
+    if CAPTURING.with(|c| c.replace(true)) {
+        return (f(), String::new());
+    }
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            CAPTURING.with(|c| c.set(false));
+        }
+    }
+    let _reset = Reset;
+    let (result, text) = redirect::capture_stderr(f);
+    if !text.is_empty() {
+        handler(&ErrorMessage { origin, text: &text });
+    }
+    (result, text)
+}
+
+#[cfg(all(unix, any(feature="graphics", feature="audio")))]
+mod redirect {
+    use std::fs::{self, File, OpenOptions};
+    use std::io::{Read, Seek, SeekFrom};
+    use std::os::raw::c_int;
+    use std::os::unix::io::AsRawFd;
+    use std::process;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    extern "C" {
+        fn dup(fd: c_int) -> c_int;
+        fn dup2(src: c_int, dst: c_int) -> c_int;
+        fn close(fd: c_int) -> c_int;
+    }
+
+    const STDERR: c_int = 2;
+
+    /// The stderr file descriptor is process-global, only one capture may be active.
+    static LOCK: Mutex<()> = Mutex::new(());
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// Restores the original stderr when dropped, even if the captured call panics.
+    struct Restore(c_int);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = dup2(self.0, STDERR);
+                let _ = close(self.0);
+            }
+        }
+    }
+
+    fn scratch_file() -> Option<File> {
+        let path = ::std::env::temp_dir().join(format!("rust-sfml-err-{}-{}",
+                                                       process::id(),
+                                                       COUNTER.fetch_add(1, Ordering::Relaxed)));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .ok();
+        let _ = fs::remove_file(&path);
+        file
+    }
+
+    pub fn capture_stderr<R, F: FnOnce() -> R>(f: F) -> (R, String) {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = match scratch_file() {
+            Some(file) => file,
+            None => return (f(), String::new()),
+        };
+        let saved = unsafe { dup(STDERR) };
+        if saved < 0 {
+            return (f(), String::new());
+        }
+        let restore = Restore(saved);
+        if unsafe { dup2(file.as_raw_fd(), STDERR) } < 0 {
+            return (f(), String::new());
+        }
+        let result = f();
+        drop(restore);
+        let mut bytes = Vec::new();
+        if file.seek(SeekFrom::Start(0)).is_ok() {
+            let _ = file.read_to_end(&mut bytes);
+        }
+        (result, String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+#[cfg(all(not(unix), any(feature="graphics", feature="audio")))]
+mod redirect {
+    pub fn capture_stderr<R, F: FnOnce() -> R>(f: F) -> (R, String) {
+        (f(), String::new())
+    }
+}
@@ -0,0 +1,259 @@
+#[cfg(test)]
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::rc::Rc;
+use system::{Clock, Time};
+
+/// Where a `GameClock` takes its time from.
+#[derive(Debug)]
+enum Source {
+    /// A root clock follows the real time.
+    Clock(Clock),
+    /// A child clock follows the time of its parent.
+    Parent(Rc<RefCell<State>>),
+    /// A time set by hand.
+    #[cfg(test)]
+    Manual(Rc<Cell<Time>>),
+}
+
+/// Times are kept in microseconds as `f64`, so that scaled times keep their fractional
+/// microseconds and don't drift depending on how often the clock is polled.
+#[derive(Debug)]
+struct State {
+    source: Source,
+    /// The time of the source at the last update.
+    last_source: f64,
+    /// The time accumulated by this clock since its creation, children follow this one.
+    total: f64,
+    /// The value of `total` at the last restart.
+    origin: f64,
+    paused: bool,
+    scale: f64,
+}
+
+impl State {
+    fn source_time(&mut self) -> f64 {
+        match self.source {
+            Source::Clock(ref clock) => clock.elapsed_time().as_microseconds() as f64,
+            Source::Parent(ref parent) => parent.borrow_mut().update(),
+            #[cfg(test)]
+            Source::Manual(ref time) => time.get().as_microseconds() as f64,
+        }
+    }
+
+    /// Accumulate the time elapsed in the source since the last update, and return the total.
+    fn update(&mut self) -> f64 {
+        let now = self.source_time();
+        let delta = now - self.last_source;
+        self.last_source = now;
+        if !self.paused {
+            self.total += delta * self.scale;
+        }
+        self.total
+    }
+
+    /// Return the time elapsed since the last restart.
+    fn elapsed(&mut self) -> Time {
+        let total = self.update();
+        Time::microseconds((total - self.origin) as i64)
+    }
+}
+
+/// A clock for game time, that can be paused and slowed down or sped up.
+///
+/// A root `GameClock` follows the real time, like `Clock`. Child clocks created with
+/// `GameClock::child` follow the time of their parent instead, so pausing or scaling a
+/// parent also pauses or scales all its children, on top of their own settings.
+/// This allows e.g. a gameplay clock that can be paused independently of the UI animations,
+/// both being driven by a root clock that can be slowed down for debugging.
+///
+/// Changes to the pause state and scale only affect the time elapsed after them.
+///
+/// # Usage example
+///
+/// ```
+/// # use sfml::system::GameClock;
+/// let mut root = GameClock::new();
+/// let mut gameplay = root.child();
+/// let ui = root.child();
+///
+/// // Slow motion for everything
+/// root.set_scale(0.5);
+/// // Pause the game, the UI keeps running in slow motion
+/// gameplay.pause();
+///
+/// let dt = gameplay.restart();
+/// # let _ = (dt, ui.elapsed_time());
+/// ```
+#[derive(Debug)]
+pub struct GameClock {
+    state: Rc<RefCell<State>>,
+}
+
+impl GameClock {
+    /// Creates a new root clock following the real time, and starts it.
+    pub fn new() -> GameClock {
+        GameClock::with_source(Source::Clock(Clock::start()))
+    }
+
+    /// Creates a clock following the time of this clock, and starts it.
+    ///
+    /// The child is running with a scale of 1, it only inherits the pause state and scale
+    /// of its parents through the time it receives from them.
+    /// It keeps following this clock even if this `GameClock` is dropped.
+    pub fn child(&self) -> GameClock {
+        GameClock::with_source(Source::Parent(self.state.clone()))
+    }
+
+    fn with_source(source: Source) -> GameClock {
+        let mut state = State {
+            source,
+            last_source: 0.,
+            total: 0.,
+            origin: 0.,
+            paused: false,
+            scale: 1.,
+        };
+        state.last_source = state.source_time();
+        GameClock { state: Rc::new(RefCell::new(state)) }
+    }
+
+    /// Gets the game time elapsed since the clock was started or last restarted.
+    pub fn elapsed_time(&self) -> Time {
+        self.state.borrow_mut().elapsed()
+    }
+
+    /// Restarts the clock.
+    ///
+    /// This function puts the time counter back to zero, the pause state and scale are kept.
+    /// Restarting a clock doesn't affect its children.
+    /// It also returns the game time elapsed since the clock was started.
+    pub fn restart(&mut self) -> Time {
+        let mut state = self.state.borrow_mut();
+        let elapsed = state.elapsed();
+        state.origin = state.total;
+        elapsed
+    }
+
+    /// Pauses the clock. The elapsed time stops advancing until `resume` is called.
+    pub fn pause(&mut self) {
+        self.set_paused(true)
+    }
+
+    /// Resumes the clock after a `pause`.
+    pub fn resume(&mut self) {
+        self.set_paused(false)
+    }
+
+    /// Pauses or resumes the clock.
+    pub fn set_paused(&mut self, paused: bool) {
+        let mut state = self.state.borrow_mut();
+        let _ = state.update();
+        state.paused = paused;
+    }
+
+    /// Tells whether this clock is paused.
+    ///
+    /// This only reflects the clock's own state: a running child of a paused clock doesn't
+    /// advance either. See `is_running`.
+    pub fn is_paused(&self) -> bool {
+        self.state.borrow().paused
+    }
+
+    /// Tells whether the time of this clock advances, i.e. neither it nor any of its parents
+    /// are paused.
+    pub fn is_running(&self) -> bool {
+        let mut state = self.state.clone();
+        loop {
+            let next = {
+                let current = state.borrow();
+                if current.paused {
+                    return false;
+                }
+                match current.source {
+                    Source::Parent(ref parent) => parent.clone(),
+                    _ => return true,
+                }
+            };
+            state = next;
+        }
+    }
+
+    /// Sets the speed of the clock relative to its source.
+    ///
+    /// A scale of 2 makes the time advance twice as fast, 0.5 gives slow motion.
+    /// Negative scales make the time go backward.
+    pub fn set_scale(&mut self, scale: f32) {
+        let mut state = self.state.borrow_mut();
+        let _ = state.update();
+        state.scale = f64::from(scale);
+    }
+
+    /// Gets the speed of the clock relative to its source.
+    pub fn scale(&self) -> f32 {
+        self.state.borrow().scale as f32
+    }
+
+    /// Gets the speed of the clock relative to the real time, combining the scales of all
+    /// its parents. Pause states are not taken into account.
+    pub fn effective_scale(&self) -> f32 {
+        let mut state = self.state.clone();
+        let mut scale = 1.;
+        loop {
+            let next = {
+                let current = state.borrow();
+                scale *= current.scale;
+                match current.source {
+                    Source::Parent(ref parent) => parent.clone(),
+                    _ => return scale as f32,
+                }
+            };
+            state = next;
+        }
+    }
+}
+
+impl Default for GameClock {
+    /// Equivalent to `GameClock::new()`.
+    fn default() -> Self {
+        GameClock::new()
+    }
+}
+
+#[test]
+fn pause_scale_and_children() {
+    let now = Rc::new(Cell::new(Time::microseconds(0)));
+    let advance = |us: i64| now.set(now.get() + Time::microseconds(us));
+    let mut root = GameClock::with_source(Source::Manual(now.clone()));
+    let mut child = root.child();
+
+    advance(100);
+    assert_eq!(root.elapsed_time(), Time::microseconds(100));
+    // Fractions of microseconds are kept however often the clock is polled
+    root.set_scale(0.5);
+    for _ in 0..1000 {
+        advance(1);
+        let _ = root.elapsed_time();
+    }
+    assert_eq!(root.restart(), Time::microseconds(600));
+    assert_eq!(child.elapsed_time(), Time::microseconds(600));
+
+    // A child inherits the scale and pause state of its parent
+    child.set_scale(4.);
+    assert_eq!(child.effective_scale(), 2.);
+    advance(100);
+    assert_eq!(root.elapsed_time(), Time::microseconds(50));
+    assert_eq!(child.restart(), Time::microseconds(800));
+    root.pause();
+    advance(100);
+    assert!(!child.is_running() && !child.is_paused());
+    assert_eq!(child.elapsed_time(), Time::microseconds(0));
+    root.resume();
+    child.pause();
+    advance(100);
+    assert_eq!(root.elapsed_time(), Time::microseconds(100));
+    assert_eq!(child.elapsed_time(), Time::microseconds(0));
+    child.resume();
+    advance(10);
+    assert_eq!(child.elapsed_time(), Time::microseconds(20));
+}
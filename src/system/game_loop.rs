@@ -0,0 +1,253 @@
+use std::collections::VecDeque;
+use system::{Clock, Time};
+
+/// Number of frames the statistics are computed over.
+const HISTORY: usize = 120;
+
+/// Statistics about the frames run by a `GameLoop`.
+///
+/// Averages and percentiles are computed over the last 120 frames.
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    frame_times: VecDeque<Time>,
+    updates: u32,
+    frame_count: u64,
+    skipped_time: Time,
+}
+
+impl FrameStats {
+    fn new() -> FrameStats {
+        FrameStats {
+            frame_times: VecDeque::with_capacity(HISTORY),
+            updates: 0,
+            frame_count: 0,
+            skipped_time: Time::microseconds(0),
+        }
+    }
+
+    fn record(&mut self, frame_time: Time, updates: u32) {
+        if self.frame_times.len() == HISTORY {
+            let _ = self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        self.updates = updates;
+        self.frame_count += 1;
+    }
+
+    /// Returns the average number of frames per second.
+    pub fn fps(&self) -> f32 {
+        let total: Time = self.frame_times.iter().sum();
+        if total.as_microseconds() <= 0 {
+            0.
+        } else {
+            (self.frame_times.len() as f64 / total.as_secs_f64()) as f32
+        }
+    }
+
+    /// Returns the duration of the last frame, before clamping.
+    pub fn frame_time(&self) -> Time {
+        self.frame_times.back().cloned().unwrap_or(Time::microseconds(0))
+    }
+
+    /// Returns the frame duration below which `percentile` percent of the recent frames fall.
+    ///
+    /// For instance `frame_time_percentile(99.)` gives the duration of the slowest frames,
+    /// ignoring the worst 1%. `percentile` is clamped to `0..=100`.
+    pub fn frame_time_percentile(&self, percentile: f32) -> Time {
+        if self.frame_times.is_empty() {
+            return Time::microseconds(0);
+        }
+        let mut sorted: Vec<Time> = self.frame_times.iter().cloned().collect();
+        sorted.sort();
+        let fraction = f64::from(percentile.clamp(0., 100.)) / 100.;
+        let index = ((sorted.len() - 1) as f64 * fraction).round() as usize;
+        sorted[index]
+    }
+
+    /// Returns the number of fixed updates run during the last frame.
+    pub fn updates(&self) -> u32 {
+        self.updates
+    }
+
+    /// Returns the number of frames run since the loop was created.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Returns the total simulation time dropped because frames took longer than the
+    /// maximum frame time.
+    pub fn skipped_time(&self) -> Time {
+        self.skipped_time
+    }
+}
+
+/// A fixed-timestep game loop.
+///
+/// Each frame, the real time elapsed since the previous frame is accumulated and consumed
+/// by running the `update` callback with a fixed timestep, as many times as needed.
+/// Then the `render` callback runs once, with an interpolation factor (`alpha`, between 0
+/// and 1) telling how far the simulation is between the last update and the next one.
+///
+/// Frames longer than the maximum frame time (250 milliseconds by default) are clamped,
+/// so that a slow frame (or a breakpoint) doesn't trigger an ever growing number of updates.
+///
+/// `RenderWindow::run_game_loop` drives a loop with the events and display of a window.
+///
+/// # Usage example
+///
+/// ```no_run
+/// use sfml::graphics::{Color, RenderTarget, RenderWindow};
+/// use sfml::system::{GameLoop, Time};
+/// use sfml::window::{Event, style};
+///
+/// struct Game {
+///     position: f32,
+///     previous: f32,
+/// }
+///
+/// let mut window = RenderWindow::new((800, 600), "Game", style::CLOSE, &Default::default());
+/// let mut game = Game { position: 0., previous: 0. };
+/// let mut game_loop = GameLoop::new(60);
+///
+/// window.run_game_loop(&mut game_loop,
+///                      &mut game,
+///                      |_, window, event| if let Event::Closed = event { window.close() },
+///                      |game, dt| {
+///                          game.previous = game.position;
+///                          game.position += 100. * dt.as_seconds();
+///                      },
+///                      |game, window, alpha| {
+///                          let x = game.previous + (game.position - game.previous) * alpha;
+///                          window.clear(&Color::black());
+///                          // draw at x..
+///                      });
+/// ```
+#[derive(Debug)]
+pub struct GameLoop {
+    timestep: Time,
+    max_frame_time: Time,
+    clock: Clock,
+    accumulator: Time,
+    stats: FrameStats,
+}
+
+impl GameLoop {
+    /// Creates a loop running `updates_per_second` fixed updates per second.
+    ///
+    /// # Panics
+    /// Panics if `updates_per_second` is 0.
+    pub fn new(updates_per_second: u32) -> GameLoop {
+        assert!(updates_per_second > 0, "the update rate must be positive");
+        GameLoop::with_timestep(Time::microseconds(1_000_000 / i64::from(updates_per_second)))
+    }
+
+    /// Creates a loop running fixed updates of `timestep`.
+    ///
+    /// # Panics
+    /// Panics if `timestep` isn't positive.
+    pub fn with_timestep(timestep: Time) -> GameLoop {
+        assert!(timestep.as_microseconds() > 0, "the timestep must be positive");
+        GameLoop {
+            timestep,
+            max_frame_time: Time::microseconds(250_000),
+            clock: Clock::start(),
+            accumulator: Time::microseconds(0),
+            stats: FrameStats::new(),
+        }
+    }
+
+    /// Returns the duration of a fixed update.
+    pub fn timestep(&self) -> Time {
+        self.timestep
+    }
+
+    /// Sets the longest frame duration taken into account, longer frames are clamped.
+    pub fn set_max_frame_time(&mut self, max_frame_time: Time) {
+        self.max_frame_time = max_frame_time;
+    }
+
+    /// Returns the longest frame duration taken into account.
+    pub fn max_frame_time(&self) -> Time {
+        self.max_frame_time
+    }
+
+    /// Returns the statistics of the recent frames.
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Forgets the time elapsed since the last frame.
+    ///
+    /// Call this after a long pause (e.g. loading a level) so that the next frame doesn't
+    /// try to catch up with it.
+    pub fn reset(&mut self) {
+        let _ = self.clock.restart();
+        self.accumulator = Time::microseconds(0);
+    }
+
+    /// Runs one frame: the fixed updates needed to catch up with the real time, then a render.
+    ///
+    /// `update` receives the timestep, `render` the interpolation factor between the
+    /// last two updates.
+    pub fn frame<S, U, R>(&mut self, state: &mut S, update: U, render: R)
+        where U: FnMut(&mut S, Time),
+              R: FnMut(&mut S, f32)
+    {
+        let frame_time = self.clock.restart();
+        self.step(frame_time, state, update, render)
+    }
+
+    /// Runs a frame that lasted `frame_time`.
+    fn step<S, U, R>(&mut self, frame_time: Time, state: &mut S, mut update: U, mut render: R)
+        where U: FnMut(&mut S, Time),
+              R: FnMut(&mut S, f32)
+    {
+        let clamped = if frame_time > self.max_frame_time {
+            self.stats.skipped_time += frame_time - self.max_frame_time;
+            self.max_frame_time
+        } else {
+            frame_time
+        };
+        self.accumulator += clamped;
+        let mut updates = 0;
+        while self.accumulator >= self.timestep {
+            update(state, self.timestep);
+            self.accumulator -= self.timestep;
+            updates += 1;
+        }
+        self.stats.record(frame_time, updates);
+        let alpha = self.accumulator.as_secs_f64() / self.timestep.as_secs_f64();
+        render(state, alpha as f32);
+    }
+}
+
+#[test]
+fn fixed_timestep() {
+    let mut game_loop = GameLoop::new(100);
+    let mut updates = Vec::new();
+    let mut alpha = -1.;
+    let mut run = |game_loop: &mut GameLoop, ms: i64| {
+        game_loop.step(Time::microseconds(ms * 1000),
+                       &mut updates,
+                       |updates, dt| updates.push(dt),
+                       |_, a| alpha = a);
+        alpha
+    };
+
+    // The remainder of a frame is carried over to the next ones
+    assert_eq!(run(&mut game_loop, 25), 0.5);
+    assert_eq!(game_loop.stats().updates(), 2);
+    assert_eq!(run(&mut game_loop, 4), 0.9);
+    assert_eq!(game_loop.stats().updates(), 0);
+    assert_eq!(run(&mut game_loop, 6), 0.5);
+    assert_eq!(game_loop.stats().updates(), 1);
+
+    // Long frames are clamped to the maximum frame time
+    game_loop.set_max_frame_time(Time::microseconds(100_000));
+    assert_eq!(run(&mut game_loop, 1000), 0.5);
+    assert_eq!(game_loop.stats().updates(), 10);
+    assert_eq!(game_loop.stats().skipped_time(), Time::microseconds(900_000));
+    assert_eq!(game_loop.stats().frame_time(), Time::microseconds(1_000_000));
+    assert_eq!(game_loop.stats().frame_count(), 4);
+    assert_eq!(updates, vec![Time::microseconds(10_000); 13]);
+}
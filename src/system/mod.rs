@@ -1,19 +1,29 @@
 //! Base module of SFML, defining various utilities.
 //!
-//! It provides vector types, timing types.
+//! It provides vector types, timing types, and access to SFML's error stream.
 //!
 
 pub use self::clock::Clock;
+#[cfg(feature="log")]
+pub use self::err::log_handler;
+pub use self::err::{ErrorMessage, reset_error_handler, set_error_handler};
+pub use self::game_clock::GameClock;
+pub use self::game_loop::{FrameStats, GameLoop};
 pub use self::sf_bool::{FALSE as SF_FALSE, SfBool, TRUE as SF_TRUE};
 pub use self::sleep::sleep;
-pub use self::time::{Time, ZERO as TIME_ZERO};
+pub use self::time::{Time, TryFromTimeError, ZERO as TIME_ZERO};
+pub use self::transform::Transform;
 pub use self::vector2::{Vector2, Vector2f, Vector2i, Vector2u};
 pub use self::vector3::{Vector3, Vector3f, Vector3i};
 
 mod time;
 mod clock;
+mod game_clock;
+mod game_loop;
 mod sleep;
+mod transform;
 mod vector2;
 mod vector3;
 mod sf_bool;
+pub(crate) mod err;
 pub mod raw_conv;
@@ -1,5 +1,10 @@
 use csfml_system_sys::*;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::iter::Sum;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+use std::time::Duration;
 use system::raw_conv::{FromRaw, Raw};
 
 /// Represents a time value.
@@ -31,6 +36,20 @@ use system::raw_conv::{FromRaw, Raw};
 /// assert_eq!(t3.as_seconds(), -0.8);
 /// ```
 ///
+/// Times convert to and from `std::time::Duration`. As a `Duration` can't be negative,
+/// the conversion from `Time` is fallible:
+///
+/// ```
+/// # use sfml::system::Time;
+/// use std::convert::TryFrom;
+/// use std::time::Duration;
+///
+/// let t = Time::from(Duration::from_millis(1500));
+/// assert_eq!(t.to_string(), "1.5s");
+/// assert_eq!(Duration::try_from(t), Ok(Duration::from_millis(1500)));
+/// assert!(Duration::try_from(-t).is_err());
+/// ```
+///
 /// # See also
 /// - `Clock`
 #[derive(Copy, Clone, Debug)]
@@ -86,6 +105,110 @@ impl Time {
     pub fn as_microseconds(&self) -> i64 {
         unsafe { sfTime_asMicroseconds(self.0) }
     }
+
+    /// Returns the time value as a number of seconds, in double precision.
+    ///
+    /// Unlike `as_seconds`, this doesn't lose precision for times longer than a few minutes.
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0.microseconds as f64 / 1_000_000.
+    }
+
+    /// Checked addition. Returns `None` if the result overflows.
+    pub fn checked_add(self, rhs: Time) -> Option<Time> {
+        self.0.microseconds.checked_add(rhs.0.microseconds).map(Time::microseconds)
+    }
+
+    /// Checked subtraction. Returns `None` if the result overflows.
+    pub fn checked_sub(self, rhs: Time) -> Option<Time> {
+        self.0.microseconds.checked_sub(rhs.0.microseconds).map(Time::microseconds)
+    }
+
+    /// Checked multiplication. Returns `None` if the result overflows.
+    pub fn checked_mul(self, rhs: i64) -> Option<Time> {
+        self.0.microseconds.checked_mul(rhs).map(Time::microseconds)
+    }
+
+    /// Checked division. Returns `None` if `rhs` is zero or the result overflows.
+    pub fn checked_div(self, rhs: i64) -> Option<Time> {
+        self.0.microseconds.checked_div(rhs).map(Time::microseconds)
+    }
+
+    /// Saturating addition. Clamps the result to the range of representable times.
+    pub fn saturating_add(self, rhs: Time) -> Time {
+        Time::microseconds(self.0.microseconds.saturating_add(rhs.0.microseconds))
+    }
+
+    /// Saturating subtraction. Clamps the result to the range of representable times.
+    pub fn saturating_sub(self, rhs: Time) -> Time {
+        Time::microseconds(self.0.microseconds.saturating_sub(rhs.0.microseconds))
+    }
+
+    /// Saturating multiplication. Clamps the result to the range of representable times.
+    pub fn saturating_mul(self, rhs: i64) -> Time {
+        Time::microseconds(self.0.microseconds.saturating_mul(rhs))
+    }
+}
+
+impl From<Duration> for Time {
+    /// Converts a `Duration` to a `Time`, truncated to microseconds.
+    ///
+    /// Durations too long to be represented saturate to the largest `Time`.
+    fn from(duration: Duration) -> Time {
+        Time::microseconds(i64::try_from(duration.as_micros()).unwrap_or(i64::MAX))
+    }
+}
+
+/// The error returned when converting a negative `Time` to a `Duration`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromTimeError(());
+
+impl fmt::Display for TryFromTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("cannot convert a negative time to a duration")
+    }
+}
+
+impl Error for TryFromTimeError {}
+
+impl TryFrom<Time> for Duration {
+    type Error = TryFromTimeError;
+
+    /// Converts a `Time` to a `Duration`, failing if the time is negative.
+    fn try_from(time: Time) -> Result<Duration, TryFromTimeError> {
+        u64::try_from(time.0.microseconds)
+            .map(Duration::from_micros)
+            .map_err(|_| TryFromTimeError(()))
+    }
+}
+
+impl Sum for Time {
+    fn sum<I: Iterator<Item = Time>>(iter: I) -> Time {
+        iter.fold(ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Time> for Time {
+    fn sum<I: Iterator<Item = &'a Time>>(iter: I) -> Time {
+        iter.fold(ZERO, |acc, &t| acc + t)
+    }
+}
+
+impl fmt::Display for Time {
+    /// Formats the time with the largest unit that keeps it above 1 (`s`, `ms` or `µs`).
+    ///
+    /// The precision, if given, applies to the number of that unit, e.g. `{:.2}` gives `1.50s`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let micros = self.0.microseconds;
+        let (value, unit) = match micros.unsigned_abs() {
+            0..=999 => (micros as f64, "µs"),
+            1_000..=999_999 => (micros as f64 / 1_000., "ms"),
+            _ => (micros as f64 / 1_000_000., "s"),
+        };
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}{}", precision, value, unit),
+            None => write!(f, "{}{}", value, unit),
+        }
+    }
 }
 
 impl Neg for Time {
@@ -244,3 +367,23 @@ impl FromRaw for Time {
 
 /// Predefined "zero" time value.
 pub const ZERO: Time = Time(sfTime { microseconds: 0 });
+
+#[test]
+fn duration_interop() {
+    assert_eq!(Time::from(Duration::new(2, 500_999)), Time::microseconds(2_000_500));
+    assert_eq!(Time::from(Duration::new(u64::MAX, 0)), Time::microseconds(i64::MAX));
+    assert_eq!(Duration::try_from(Time::microseconds(1_250)),
+               Ok(Duration::from_micros(1_250)));
+    assert_eq!(Duration::try_from(Time::microseconds(-1)),
+               Err(TryFromTimeError(())));
+    assert_eq!(Time::microseconds(i64::MAX).checked_add(Time::microseconds(1)),
+               None);
+    assert_eq!(Time::microseconds(i64::MIN).saturating_sub(Time::microseconds(1)),
+               Time::microseconds(i64::MIN));
+    assert_eq!(Time::microseconds(7).checked_div(0), None);
+    let times = [Time::microseconds(1), Time::microseconds(2)];
+    assert_eq!(times.iter().sum::<Time>(), Time::microseconds(3));
+    assert_eq!(Time::microseconds(-800_000).to_string(), "-800ms");
+    assert_eq!(Time::microseconds(12).to_string(), "12µs");
+    assert_eq!(format!("{:.2}", Time::microseconds(1_500_000)), "1.50s");
+}
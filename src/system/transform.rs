@@ -0,0 +1,325 @@
+use std::ops::{Mul, MulAssign};
+use system::Vector2f;
+
+/// Define a 3x3 transform matrix.
+///
+/// A `Transform` specifies how to translate,
+/// rotate, scale, shear, project, whatever things.
+///
+/// The matrix is handled in Rust, so transforms can be built, combined and applied
+/// without the graphics library. It is also exported as `graphics::Transform`.
+///
+/// # Usage example
+///
+/// ```
+/// # use sfml::system::{Transform, Vector2f};
+/// let transform = Transform::identity().translated(10., 0.).rotated(90.);
+/// // Rotate the point, then translate it
+/// let point = transform * Vector2f::new(1., 0.);
+/// assert!((point.x - 10.).abs() < 1e-5 && (point.y - 1.).abs() < 1e-5);
+/// assert_eq!(transform * transform.inverse(), Transform::identity());
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    matrix: [f32; 9],
+}
+
+/// The tolerance used by the `PartialEq` implementation of `Transform`.
+const EPSILON: f32 = 1e-5;
+
+impl Transform {
+    /// Create a new transform from a 3x3 matrix
+    ///
+    /// # Arguments
+    ///
+    /// * matrix - An array supplying the matrix
+    ///
+    ///   Here is an illustration of how the array elements correspond to the matrix elements:
+    ///
+    ///   ```text
+    ///   [(0, 0), (0, 1), (0, 2),
+    ///    (1, 0), (1, 1), (1, 2),
+    ///    (2, 0), (2, 1), (2, 2)]
+    ///   ```
+    ///
+    /// Return a new Transform
+    pub fn new(matrix: [f32; 9]) -> Transform {
+        Transform { matrix }
+    }
+
+    /// Return the 3x3 matrix, its elements in the order given to `new`
+    pub fn as_array(&self) -> &[f32; 9] {
+        &self.matrix
+    }
+
+    /// Return the matrix as a 4x4 matrix in column-major order, as expected by OpenGL
+    pub fn matrix(&self) -> [f32; 16] {
+        let m = &self.matrix;
+        [m[0], m[3], 0., m[6], m[1], m[4], 0., m[7], 0., 0., 1., 0., m[2], m[5], 0., m[8]]
+    }
+
+    /// The identity transform (does nothing)
+    pub fn identity() -> Self {
+        Transform::new([1., 0., 0., 0., 1., 0., 0., 0., 1.])
+    }
+
+    /// Return the inverse of a transform
+    ///
+    /// If the inverse cannot be computed, a new identity transform
+    /// is returned.
+    ///
+    /// Return the inverse matrix
+    pub fn inverse(&self) -> Transform {
+        let m = &self.matrix;
+        let det = m[0] * (m[8] * m[4] - m[5] * m[7]) - m[3] * (m[8] * m[1] - m[2] * m[7]) +
+                  m[6] * (m[5] * m[1] - m[2] * m[4]);
+        if det == 0. {
+            return Transform::identity();
+        }
+        Transform::new([(m[8] * m[4] - m[5] * m[7]) / det,
+                        -(m[8] * m[1] - m[2] * m[7]) / det,
+                        (m[5] * m[1] - m[2] * m[4]) / det,
+                        -(m[8] * m[3] - m[5] * m[6]) / det,
+                        (m[8] * m[0] - m[2] * m[6]) / det,
+                        -(m[5] * m[0] - m[2] * m[3]) / det,
+                        (m[7] * m[3] - m[4] * m[6]) / det,
+                        -(m[7] * m[0] - m[1] * m[6]) / det,
+                        (m[4] * m[0] - m[1] * m[3]) / det])
+    }
+
+    /// Combine two transforms
+    ///
+    /// The result is a transform that is equivalent to applying
+    /// other followed by transform. Mathematically, it is
+    /// equivalent to a matrix multiplication, see also `Mul`.
+    ///
+    /// # Arguments
+    /// * other - Transform to combine to transform
+    pub fn combine(&mut self, other: &Transform) {
+        let a = &self.matrix;
+        let b = &other.matrix;
+        let mut result = [0.; 9];
+        for row in 0..3 {
+            for col in 0..3 {
+                result[row * 3 + col] = a[row * 3] * b[col] + a[row * 3 + 1] * b[3 + col] +
+                                        a[row * 3 + 2] * b[6 + col];
+            }
+        }
+        self.matrix = result;
+    }
+
+    /// Combine a transform with a translation
+    ///
+    /// # Arguments
+    /// * x - Offset to apply on X axis
+    /// * y - Offset to apply on Y axis
+    pub fn translate(&mut self, x: f32, y: f32) {
+        self.combine(&Transform::new([1., 0., x, 0., 1., y, 0., 0., 1.]))
+    }
+
+    /// Combine the current transform with a rotation
+    ///
+    /// # Arguments
+    /// * angle - Rotation angle, in degrees
+    pub fn rotate(&mut self, angle: f32) {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        self.combine(&Transform::new([cos, -sin, 0., sin, cos, 0., 0., 0., 1.]))
+    }
+
+    /// Combine the current transform with a rotation
+    ///
+    /// The center of rotation is provided for convenience as a second
+    /// argument, so that you can build rotations around arbitrary points
+    /// more easily (and efficiently) than the usual
+    /// [translate(-center), rotate(angle), translate(center)].
+    ///
+    /// # Arguments
+    /// * angle - Rotation angle, in degrees
+    /// * center_x - X coordinate of the center of rotation
+    /// * center_y - Y coordinate of the center of rotation
+    pub fn rotate_with_center(&mut self, angle: f32, center_x: f32, center_y: f32) {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        self.combine(&Transform::new([cos,
+                                      -sin,
+                                      center_x * (1. - cos) + center_y * sin,
+                                      sin,
+                                      cos,
+                                      center_y * (1. - cos) - center_x * sin,
+                                      0.,
+                                      0.,
+                                      1.]))
+    }
+
+    /// Combine the current transform with a scaling
+    ///
+    /// # Arguments
+    /// * scale_x - Scaling factor on the X axis
+    /// * scale_y - Scaling factor on the Y axis
+    pub fn scale(&mut self, scale_x: f32, scale_y: f32) {
+        self.combine(&Transform::new([scale_x, 0., 0., 0., scale_y, 0., 0., 0., 1.]))
+    }
+
+    /// Combine the current transform with a scaling
+    ///
+    /// The center of scaling is provided for convenience as a second
+    /// argument, so that you can build scaling around arbitrary points
+    /// more easily (and efficiently) than the usual
+    /// [translate(-center), scale(factors), translate(center)]
+    ///
+    /// # Arguments
+    /// * scale_x - Scaling factor on X axis
+    /// * scale_y - Scaling factor on Y axis
+    /// * center_x - X coordinate of the center of scaling
+    /// * center_y - Y coordinate of the center of scaling
+    pub fn scale_with_center(&mut self, scale_x: f32, scale_y: f32, center_x: f32, center_y: f32) {
+        self.combine(&Transform::new([scale_x,
+                                      0.,
+                                      center_x * (1. - scale_x),
+                                      0.,
+                                      scale_y,
+                                      center_y * (1. - scale_y),
+                                      0.,
+                                      0.,
+                                      1.]))
+    }
+
+    /// Return this transform combined with a translation, see `translate`
+    pub fn translated(mut self, x: f32, y: f32) -> Transform {
+        self.translate(x, y);
+        self
+    }
+
+    /// Return this transform combined with a rotation, see `rotate`
+    pub fn rotated(mut self, angle: f32) -> Transform {
+        self.rotate(angle);
+        self
+    }
+
+    /// Return this transform combined with a rotation around a center, see `rotate_with_center`
+    pub fn rotated_with_center(mut self, angle: f32, center_x: f32, center_y: f32) -> Transform {
+        self.rotate_with_center(angle, center_x, center_y);
+        self
+    }
+
+    /// Return this transform combined with a scaling, see `scale`
+    pub fn scaled(mut self, scale_x: f32, scale_y: f32) -> Transform {
+        self.scale(scale_x, scale_y);
+        self
+    }
+
+    /// Return this transform combined with a scaling around a center, see `scale_with_center`
+    pub fn scaled_with_center(mut self,
+                              scale_x: f32,
+                              scale_y: f32,
+                              center_x: f32,
+                              center_y: f32)
+                              -> Transform {
+        self.scale_with_center(scale_x, scale_y, center_x, center_y);
+        self
+    }
+
+    /// Apply a transform to a 2D point
+    ///
+    /// # Arguments
+    /// * point - Point to transform
+    ///
+    /// Return a transformed point
+    pub fn transform_point(&self, point: &Vector2f) -> Vector2f {
+        let m = &self.matrix;
+        Vector2f::new(m[0] * point.x + m[1] * point.y + m[2],
+                      m[3] * point.x + m[4] * point.y + m[5])
+    }
+
+    /// Split an affine transform into a translation, a rotation and a scale
+    ///
+    /// Return `(translation, rotation, scale)`, the rotation being in degrees in `[0, 360)`.
+    /// Applying them as `Transformable` does (scale, then rotate, then translate) gives this
+    /// transform back, as long as it contains no shear nor projection.
+    /// A mirroring is reported as a negative vertical scale.
+    pub fn decompose(&self) -> (Vector2f, f32, Vector2f) {
+        let m = &self.matrix;
+        let translation = Vector2f::new(m[2], m[5]);
+        let scale_x = m[0].hypot(m[3]);
+        if scale_x == 0. {
+            return (translation, 0., Vector2f::new(0., m[1].hypot(m[4])));
+        }
+        let scale_y = (m[0] * m[4] - m[1] * m[3]) / scale_x;
+        let mut rotation = m[3].atan2(m[0]).to_degrees();
+        if rotation < 0. {
+            rotation += 360.;
+        }
+        (translation, rotation, Vector2f::new(scale_x, scale_y))
+    }
+
+    /// Tell whether every element of the two matrices differs by at most `epsilon`,
+    /// relatively to their magnitude when it is larger than 1
+    pub fn approx_eq(&self, other: &Transform, epsilon: f32) -> bool {
+        self.matrix
+            .iter()
+            .zip(other.matrix.iter())
+            .all(|(&a, &b)| (a - b).abs() <= epsilon * a.abs().max(b.abs()).max(1.))
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Transforms are compared with a small tolerance (see `approx_eq`), to absorb rounding errors.
+impl PartialEq for Transform {
+    fn eq(&self, other: &Transform) -> bool {
+        self.approx_eq(other, EPSILON)
+    }
+}
+
+/// Combine two transforms, `(a * b) * point` is `a * (b * point)`.
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(mut self, rhs: Transform) -> Transform {
+        self.combine(&rhs);
+        self
+    }
+}
+
+impl MulAssign for Transform {
+    fn mul_assign(&mut self, rhs: Transform) {
+        self.combine(&rhs)
+    }
+}
+
+/// Transform a point, see `transform_point`.
+impl Mul<Vector2f> for Transform {
+    type Output = Vector2f;
+
+    fn mul(self, rhs: Vector2f) -> Vector2f {
+        self.transform_point(&rhs)
+    }
+}
+
+#[test]
+fn compose_invert_and_decompose() {
+    let t = Transform::identity().translated(5., -3.).rotated(30.).scaled(2., 0.5);
+    let p = Vector2f::new(1., 2.);
+    let manual = Transform::identity().translated(5., -3.) *
+                 (Transform::identity().rotated(30.) * Transform::identity().scaled(2., 0.5));
+    assert_eq!(t, manual);
+    let back = t.inverse() * (t * p);
+    assert!((back.x - p.x).abs() < 1e-4 && (back.y - p.y).abs() < 1e-4);
+
+    let (translation, rotation, scale) = t.decompose();
+    assert!((translation.x - 5.).abs() < 1e-5 && (translation.y + 3.).abs() < 1e-5);
+    assert!((rotation - 30.).abs() < 1e-3);
+    assert!((scale.x - 2.).abs() < 1e-5 && (scale.y - 0.5).abs() < 1e-5);
+
+    let around = Transform::identity().rotated_with_center(90., 1., 1.);
+    let p = around * Vector2f::new(2., 1.);
+    assert!((p.x - 1.).abs() < 1e-5 && (p.y - 2.).abs() < 1e-5);
+
+    assert_eq!(Transform::new([1., 2., 3., 4., 5., 6., 7., 8., 9.]).inverse(),
+               Transform::identity());
+    assert!(t != Transform::identity());
+}
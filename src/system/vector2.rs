@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+use std::num::TryFromIntError;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use system::raw_conv::{FromRaw, Raw};
 
@@ -18,8 +20,10 @@ use system::raw_conv::{FromRaw, Raw};
 /// - `Vector2<u32>` is `Vector2u`
 ///
 /// The `Vector2` type has a small and simple interface, its x and y members can be
-/// accessed directly (there are no accessors like `set_x()`, `get_x()`) and it contains no
-/// mathematical function like dot product, cross product, length, etc.
+/// accessed directly (there are no accessors like `set_x()`, `get_x()`).
+/// Besides the component-wise operators, it provides the usual vector math: dot and cross
+/// products for any coordinate type, and length, normalization, rotation, interpolation..
+/// for `Vector2f`. Angles are in degrees, like everywhere else in SFML.
 ///
 /// # Usage example
 ///
@@ -32,6 +36,10 @@ use system::raw_conv::{FromRaw, Raw};
 /// let v2 = v1 * 5.0;
 /// let v3 = v1 + v2;
 /// assert_ne!(v2, v3);
+///
+/// let up = Vector2f::new(0., -2.);
+/// assert_eq!(up.normalize(), Vector2f::new(0., -1.));
+/// assert_eq!(Vector2f::new(1., 0.).angle_to(up), -90.);
 /// ```
 ///
 /// Note: for 3-dimensional vectors, see `Vector3`.
@@ -58,6 +66,166 @@ impl<T> Vector2<T> {
     }
 }
 
+impl<T: Mul<Output = T> + Add<Output = T> + Sub<Output = T> + Copy> Vector2<T> {
+    /// Returns the dot product of two vectors.
+    pub fn dot(self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    /// Returns the z component of the cross product of two vectors, taken as 3D vectors
+    /// in the xy plane.
+    ///
+    /// It is positive if `rhs` is clockwise from `self` on screen (SFML's y axis points down).
+    pub fn cross(self, rhs: Self) -> T {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    /// Returns the square of the length of the vector.
+    ///
+    /// This is cheaper than `length` and enough to compare lengths.
+    pub fn length_sq(self) -> T {
+        self.dot(self)
+    }
+}
+
+impl<T: Neg<Output = T>> Vector2<T> {
+    /// Returns the vector rotated by 90 degrees, clockwise on screen.
+    pub fn perpendicular(self) -> Self {
+        Vector2 {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+}
+
+impl Vector2f {
+    /// Returns the length of the vector.
+    pub fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// Returns the vector with the same direction and a length of 1.
+    ///
+    /// The zero vector is returned unchanged.
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length == 0. {
+            self
+        } else {
+            self / length
+        }
+    }
+
+    /// Returns the distance between two points.
+    pub fn distance(self, rhs: Self) -> f32 {
+        (rhs - self).length()
+    }
+
+    /// Returns the angle of the vector in degrees, from the x axis.
+    ///
+    /// The result is in `[-180, 180]` and grows clockwise on screen, like
+    /// `Transformable::rotation`.
+    pub fn angle(self) -> f32 {
+        self.y.atan2(self.x).to_degrees()
+    }
+
+    /// Returns the signed angle in degrees, in `[-180, 180]`, to rotate `self` by
+    /// to get the direction of `rhs`.
+    pub fn angle_to(self, rhs: Self) -> f32 {
+        self.cross(rhs).atan2(self.dot(rhs)).to_degrees()
+    }
+
+    /// Returns the vector rotated by `degrees`, clockwise on screen.
+    pub fn rotate(self, degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Vector2 {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+
+    /// Linear interpolation between `self` (`t == 0`) and `rhs` (`t == 1`).
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
+
+    /// Returns the projection of the vector onto the direction of `axis`.
+    ///
+    /// Projecting onto the zero vector gives the zero vector.
+    pub fn project_onto(self, axis: Self) -> Self {
+        let length_sq = axis.length_sq();
+        if length_sq == 0. {
+            axis
+        } else {
+            axis * (self.dot(axis) / length_sq)
+        }
+    }
+
+    /// Returns the vector reflected off a surface with the given normal.
+    ///
+    /// `normal` must have a length of 1.
+    pub fn reflect(self, normal: Self) -> Self {
+        self - normal * (2. * self.dot(normal))
+    }
+
+    /// Converts to integer coordinates, truncating toward zero.
+    ///
+    /// Out of range coordinates saturate, NaN becomes 0.
+    pub fn to_i32(self) -> Vector2i {
+        Vector2::new(self.x as i32, self.y as i32)
+    }
+
+    /// Converts to unsigned coordinates, truncating toward zero.
+    ///
+    /// Out of range coordinates saturate (negative ones to 0), NaN becomes 0.
+    pub fn to_u32(self) -> Vector2u {
+        Vector2::new(self.x as u32, self.y as u32)
+    }
+}
+
+impl Vector2i {
+    /// Converts to float coordinates.
+    ///
+    /// Coordinates above 2^24 in magnitude are rounded to the nearest representable float.
+    pub fn to_f32(self) -> Vector2f {
+        Vector2::new(self.x as f32, self.y as f32)
+    }
+}
+
+impl Vector2u {
+    /// Converts to float coordinates.
+    ///
+    /// Coordinates above 2^24 are rounded to the nearest representable float.
+    pub fn to_f32(self) -> Vector2f {
+        Vector2::new(self.x as f32, self.y as f32)
+    }
+}
+
+impl TryFrom<Vector2i> for Vector2u {
+    type Error = TryFromIntError;
+
+    /// Fails if a coordinate is negative.
+    fn try_from(src: Vector2i) -> Result<Self, TryFromIntError> {
+        Ok(Vector2::new(u32::try_from(src.x)?, u32::try_from(src.y)?))
+    }
+}
+
+impl TryFrom<Vector2u> for Vector2i {
+    type Error = TryFromIntError;
+
+    /// Fails if a coordinate is above `i32::MAX`.
+    fn try_from(src: Vector2u) -> Result<Self, TryFromIntError> {
+        Ok(Vector2::new(i32::try_from(src.x)?, i32::try_from(src.y)?))
+    }
+}
+
+impl<T> From<Vector2<T>> for (T, T) {
+    /// Converts a `Vector2` to `(x, y)`.
+    fn from(src: Vector2<T>) -> Self {
+        (src.x, src.y)
+    }
+}
+
 impl<T> From<(T, T)> for Vector2<T> {
     /// Constructs a `Vector2` from `(x, y)`.
     fn from(src: (T, T)) -> Self {
@@ -233,3 +401,28 @@ impl FromRaw for Vector2f {
         Vector2f { x: raw.x, y: raw.y }
     }
 }
+
+#[test]
+fn vector_math() {
+    let v = Vector2f::new(3., 4.);
+    assert_eq!(v.length(), 5.);
+    assert_eq!(v.length_sq(), 25.);
+    assert_eq!(v.normalize(), Vector2f::new(0.6, 0.8));
+    assert_eq!(Vector2f::new(0., 0.).normalize(), Vector2f::new(0., 0.));
+    assert_eq!(v.dot(Vector2f::new(1., 1.)), 7.);
+    assert_eq!(Vector2i::new(1, 0).cross(Vector2i::new(0, 1)), 1);
+    assert_eq!(Vector2i::new(1, 2).perpendicular(), Vector2i::new(-2, 1));
+    assert_eq!(Vector2f::new(0., 1.).angle(), 90.);
+    assert_eq!(Vector2f::new(0., 1.).angle_to(Vector2f::new(1., 0.)), -90.);
+    let rotated = Vector2f::new(1., 0.).rotate(90.);
+    assert!(rotated.distance(Vector2f::new(0., 1.)) < 1e-6);
+    assert_eq!(Vector2f::new(0., 0.).lerp(v, 0.5), Vector2f::new(1.5, 2.));
+    assert_eq!(v.project_onto(Vector2f::new(2., 0.)), Vector2f::new(3., 0.));
+    assert_eq!(Vector2f::new(1., -1.).reflect(Vector2f::new(0., 1.)),
+               Vector2f::new(1., 1.));
+    assert_eq!(Vector2f::new(-1.7, 2.9).to_i32(), Vector2i::new(-1, 2));
+    assert_eq!(Vector2f::new(-1.7, 2.9).to_u32(), Vector2u::new(0, 2));
+    assert!(Vector2u::try_from(Vector2i::new(-1, 2)).is_err());
+    assert_eq!(Vector2i::try_from(Vector2u::new(1, 2)),
+               Ok(Vector2i::new(1, 2)));
+}
@@ -18,8 +18,9 @@ use system::raw_conv::{FromRaw, Raw};
 /// - `Vector3<i32>` is `Vector3i`
 ///
 /// The `Vector3` type has a small and simple interface, its x and y members can be
-/// accessed directly (there are no accessors like `set_x()`, `get_x()`) and it contains no
-/// mathematical function like dot product, cross product, length, etc.
+/// accessed directly (there are no accessors like `set_x()`, `get_x()`).
+/// Besides the component-wise operators, it provides dot and cross products for any
+/// coordinate type, and length, normalization, interpolation.. for `Vector3f`.
 ///
 /// # Usage example
 /// ```
@@ -59,6 +60,98 @@ pub type Vector3f = Vector3<f32>;
 /// `Vector3` with `i32` coordinates.
 pub type Vector3i = Vector3<i32>;
 
+impl<T: Mul<Output = T> + Add<Output = T> + Sub<Output = T> + Copy> Vector3<T> {
+    /// Returns the dot product of two vectors.
+    pub fn dot(self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    /// Returns the cross product of two vectors.
+    pub fn cross(self, rhs: Self) -> Self {
+        Vector3 {
+            x: self.y * rhs.z - self.z * rhs.y,
+            y: self.z * rhs.x - self.x * rhs.z,
+            z: self.x * rhs.y - self.y * rhs.x,
+        }
+    }
+
+    /// Returns the square of the length of the vector.
+    ///
+    /// This is cheaper than `length` and enough to compare lengths.
+    pub fn length_sq(self) -> T {
+        self.dot(self)
+    }
+}
+
+impl Vector3f {
+    /// Returns the length of the vector.
+    pub fn length(self) -> f32 {
+        self.length_sq().sqrt()
+    }
+
+    /// Returns the vector with the same direction and a length of 1.
+    ///
+    /// The zero vector is returned unchanged.
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length == 0. {
+            self
+        } else {
+            self / length
+        }
+    }
+
+    /// Returns the distance between two points.
+    pub fn distance(self, rhs: Self) -> f32 {
+        (rhs - self).length()
+    }
+
+    /// Returns the unsigned angle between two vectors, in degrees.
+    pub fn angle_to(self, rhs: Self) -> f32 {
+        self.cross(rhs).length().atan2(self.dot(rhs)).to_degrees()
+    }
+
+    /// Linear interpolation between `self` (`t == 0`) and `rhs` (`t == 1`).
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
+
+    /// Returns the projection of the vector onto the direction of `axis`.
+    ///
+    /// Projecting onto the zero vector gives the zero vector.
+    pub fn project_onto(self, axis: Self) -> Self {
+        let length_sq = axis.length_sq();
+        if length_sq == 0. {
+            axis
+        } else {
+            axis * (self.dot(axis) / length_sq)
+        }
+    }
+
+    /// Returns the vector reflected off a surface with the given normal.
+    ///
+    /// `normal` must have a length of 1.
+    pub fn reflect(self, normal: Self) -> Self {
+        self - normal * (2. * self.dot(normal))
+    }
+
+    /// Converts to integer coordinates, truncating toward zero.
+    ///
+    /// Out of range coordinates saturate, NaN becomes 0.
+    pub fn to_i32(self) -> Vector3i {
+        Vector3::new(self.x as i32, self.y as i32, self.z as i32)
+    }
+}
+
+impl Vector3i {
+    /// Converts to float coordinates.
+    ///
+    /// Coordinates above 2^24 in magnitude are rounded to the nearest representable float.
+    pub fn to_f32(self) -> Vector3f {
+        Vector3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
 macro_rules! impl_ops {
     ( $_trait:ident, $_func:ident, $( $_type:ty ),+ ) => {
         impl<T: $_trait + Copy> $_trait<T> for Vector3<T> {
@@ -187,6 +280,13 @@ impl<T: Neg<Output = T>> Neg for Vector3<T> {
     }
 }
 
+impl<T> From<Vector3<T>> for (T, T, T) {
+    /// Converts a `Vector3` to `(x, y, z)`.
+    fn from(src: Vector3<T>) -> Self {
+        (src.x, src.y, src.z)
+    }
+}
+
 impl<T> From<(T, T, T)> for Vector3<T> {
     /// Constructs a `Vector3` from `(x, y, z)`.
     fn from(src: (T, T, T)) -> Self {
@@ -220,3 +320,20 @@ impl FromRaw for Vector3f {
         }
     }
 }
+
+#[test]
+fn vector_math() {
+    let x = Vector3i::new(1, 0, 0);
+    let y = Vector3i::new(0, 1, 0);
+    assert_eq!(x.cross(y), Vector3i::new(0, 0, 1));
+    assert_eq!(x.dot(y), 0);
+    let v = Vector3f::new(2., 3., 6.);
+    assert_eq!(v.length(), 7.);
+    assert_eq!(v.normalize().length(), 1.);
+    assert_eq!(Vector3f::new(1., 0., 0.).angle_to(Vector3f::new(0., 0., 3.)), 90.);
+    assert_eq!(v.project_onto(Vector3f::new(0., 0., 2.)),
+               Vector3f::new(0., 0., 6.));
+    assert_eq!(Vector3f::new(1., -1., 0.).reflect(Vector3f::new(0., 1., 0.)),
+               Vector3f::new(1., 1., 0.));
+    assert_eq!(v.to_i32().to_f32(), v);
+}
@@ -0,0 +1,32 @@
+//! The error handler is process-global, so it is tested in its own binary, where no other
+//! test loads resources or writes to the standard error output meanwhile.
+#![cfg(unix)]
+
+extern crate sfml;
+
+use sfml::graphics::Image;
+use sfml::system;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn handler_receives_sfml_messages() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    // The handler removes itself, which must not deadlock
+    system::set_error_handler(move |msg| {
+                                  sink.lock()
+                                      .unwrap()
+                                      .push((msg.origin(), msg.text().to_owned()));
+                                  system::reset_error_handler();
+                              });
+    let error = Image::from_memory(b"not an image").unwrap_err();
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].0, "Image::from_memory");
+    assert!(seen[0].1.contains("Failed to load image"));
+    assert_eq!(error.message(), seen[0].1);
+
+    // Without a handler, nothing is captured
+    let error = Image::from_memory(b"not an image").unwrap_err();
+    assert_eq!(error.message(), "");
+}